@@ -0,0 +1,173 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{ToTokens, quote};
+use syn::{DeriveInput, Error, Expr, ExprRange, Ident, Path, RangeLimits, Type, spanned::Spanned};
+
+use crate::attrs::ContainerAttrs;
+use crate::fields::{FieldInfo, construct};
+
+/// Builds a strategy for the value `ctor` makes out of `fields`.
+pub fn fields_strategy(
+    krate: &Path,
+    name: &Ident,
+    ctor: TokenStream,
+    fields: &[FieldInfo],
+) -> Result<TokenStream, Error> {
+    let mut strategies = vec![];
+    let mut bindings = vec![];
+    for field in fields.iter().filter(|f| !f.attrs.skip) {
+        strategies.push(field_strategy(krate, name, field)?);
+        bindings.push(field.binding.to_token_stream());
+    }
+    let value = construct(ctor, fields);
+    let (Some(last), Some(last_binding)) = (strategies.pop(), bindings.pop()) else {
+        return Ok(quote! { #krate::proptest::strategy::LazyJust::new(|| #value) });
+    };
+    // nest the fields in pairs, since tuples of strategies only go up to 12
+    let strategy = strategies
+        .into_iter()
+        .rev()
+        .fold(last, |rest, strategy| quote! { (#strategy, #rest) });
+    let pattern = bindings
+        .into_iter()
+        .rev()
+        .fold(last_binding, |rest, binding| quote! { (#binding, #rest) });
+    Ok(quote! {
+        #krate::proptest::strategy::Strategy::prop_map(#strategy, |#pattern| #value)
+    })
+}
+
+/// Counts the fields which refer back to the type being derived.
+pub fn recursive_fields(name: &Ident, fields: &[FieldInfo]) -> usize {
+    fields
+        .iter()
+        .filter(|f| f.attrs.strategy.is_none() && mentions(f.ty.to_token_stream(), name))
+        .count()
+}
+
+/// Picks one of the variants. Variants with recursive fields are picked less often than
+/// the others, so that generated values stay finite.
+pub fn variants_strategy(
+    krate: &Path,
+    variants: Vec<(TokenStream, usize)>,
+) -> Result<TokenStream, Error> {
+    if variants.is_empty() {
+        return Err(Error::new(
+            Span::call_site(),
+            "arbitrary is not supported on enums without variants",
+        ));
+    }
+    let recursive: usize = variants.iter().map(|(_, count)| count).sum();
+    let weighted = variants.into_iter().map(|(strategy, count)| {
+        let weight = if count > 0 {
+            1
+        } else {
+            recursive.max(1) as u32
+        };
+        quote! { (#weight, #krate::proptest::strategy::Strategy::boxed(#strategy)) }
+    });
+    Ok(quote! {
+        #krate::proptest::strategy::Union::new_weighted(vec![#(#weighted),*])
+    })
+}
+
+/// Implements `Arbitrary` for the type, generating values with `strategy`.
+pub fn impl_arbitrary(
+    krate: &Path,
+    input: &DeriveInput,
+    container: &ContainerAttrs,
+    strategy: TokenStream,
+) -> Result<TokenStream, Error> {
+    if let Some(lifetime) = input.generics.lifetimes().next() {
+        return Err(Error::new(
+            lifetime.span(),
+            "arbitrary is not supported on types with lifetimes",
+        ));
+    }
+    let strategy = match &container.validate {
+        Some(validate) => quote! {
+            #krate::proptest::strategy::Strategy::prop_filter(
+                #strategy,
+                stringify!(#validate),
+                |value| #validate(value).is_ok(),
+            )
+        },
+        None => strategy,
+    };
+    let mut generics = input.generics.clone();
+    let params: Vec<Ident> = generics.type_params().map(|p| p.ident.clone()).collect();
+    if !params.is_empty() {
+        let predicates = generics.make_where_clause();
+        for param in params {
+            predicates.predicates.push(syn::parse_quote! {
+                #param: #krate::proptest::arbitrary::Arbitrary + 'static
+            });
+        }
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::proptest::arbitrary::Arbitrary for #name #ty_generics #where_clause {
+            type Parameters = ();
+            type Strategy = #krate::proptest::strategy::BoxedStrategy<Self>;
+
+            fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                #krate::proptest::strategy::Strategy::boxed(#strategy)
+            }
+        }
+    })
+}
+
+fn field_strategy(krate: &Path, name: &Ident, field: &FieldInfo) -> Result<TokenStream, Error> {
+    let ty = field.ty;
+    if let Some(strategy) = &field.attrs.strategy {
+        return Ok(strategy.to_token_stream());
+    }
+    if field.attrs.len.is_some() || field.attrs.non_empty {
+        return Err(Error::new(
+            ty.span(),
+            "arbitrary needs a strategy for fields with len or non_empty constraints",
+        ));
+    }
+    if let Some(range) = &field.attrs.range {
+        return Ok(range_strategy(range, ty));
+    }
+    let any = quote! { #krate::proptest::arbitrary::any::<#ty>() };
+    if !mentions(ty.to_token_stream(), name) {
+        return Ok(any);
+    }
+    // a recursive field's strategy contains itself, so only build it when it's used
+    Ok(quote! {
+        #krate::proptest::strategy::Strategy::prop_flat_map(
+            #krate::proptest::strategy::Just(()),
+            |()| #any,
+        )
+    })
+}
+
+// gives the bounds of a range literal the field's type, so proptest can pick a strategy
+fn range_strategy(range: &Expr, ty: &Type) -> TokenStream {
+    let Expr::Range(ExprRange {
+        start, limits, end, ..
+    }) = range
+    else {
+        return range.to_token_stream();
+    };
+    let start = start
+        .as_ref()
+        .map(|start| quote! { { let start: #ty = #start; start } });
+    let end = end
+        .as_ref()
+        .map(|end| quote! { { let end: #ty = #end; end } });
+    match limits {
+        RangeLimits::HalfOpen(_) => quote! { #start..#end },
+        RangeLimits::Closed(_) => quote! { #start..=#end },
+    }
+}
+
+fn mentions(tokens: TokenStream, name: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == *name || ident == "Self",
+        TokenTree::Group(group) => mentions(group.stream(), name),
+        _ => false,
+    })
+}
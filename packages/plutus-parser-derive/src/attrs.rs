@@ -0,0 +1,191 @@
+use syn::{
+    Attribute, Error, Expr, LitInt, LitStr, Meta, Path, Token, WherePredicate,
+    meta::ParseNestedMeta, punctuated::Punctuated,
+};
+
+pub enum DataFormat {
+    List,
+    Constr {
+        variant: u64,
+    },
+    /// The only field, with no wrapper. Only used by the helios convention.
+    Bare,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Convention {
+    Aiken,
+    PlutusTx,
+    Helios,
+}
+
+#[derive(Default)]
+pub struct ContainerAttrs {
+    pub format: Option<DataFormat>,
+    pub bound: Option<Vec<WherePredicate>>,
+    pub krate: Option<Path>,
+    pub validate: Option<Path>,
+    pub require_index: bool,
+    pub convention: Option<Convention>,
+    pub arbitrary: bool,
+}
+
+impl ContainerAttrs {
+    /// The path to the `plutus_parser` crate used by the generated code.
+    pub fn krate(&self) -> Path {
+        self.krate
+            .clone()
+            .unwrap_or_else(|| syn::parse_quote!(plutus_parser))
+    }
+}
+
+#[derive(Default)]
+pub struct VariantAttrs {
+    pub variant: Option<u64>,
+}
+
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub bound: Option<Vec<WherePredicate>>,
+    pub range: Option<Expr>,
+    pub len: Option<Expr>,
+    pub non_empty: bool,
+    pub flatten: bool,
+    pub pairs: bool,
+    pub index: Option<LitInt>,
+    pub convention: Option<Convention>,
+    pub strategy: Option<Expr>,
+    pub skip: bool,
+}
+
+pub fn parse_container_attrs(attrs: &[Attribute]) -> Result<ContainerAttrs, Error> {
+    let mut result = ContainerAttrs::default();
+    parse_plutus_attrs(attrs, |meta| {
+        if meta.path.is_ident("list") {
+            result.format = Some(DataFormat::List);
+            Ok(())
+        } else if meta.path.is_ident("constr") {
+            if !meta.input.is_empty() {
+                let value = meta.value()?;
+                let i: LitInt = value.parse()?;
+                result.format = Some(DataFormat::Constr {
+                    variant: i.base10_parse()?,
+                });
+            }
+            Ok(())
+        } else if meta.path.is_ident("bound") {
+            result.bound = Some(parse_bound(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("crate") {
+            let value: LitStr = meta.value()?.parse()?;
+            result.krate = Some(value.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("validate") {
+            let value: LitStr = meta.value()?.parse()?;
+            result.validate = Some(value.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("require_index") {
+            result.require_index = true;
+            Ok(())
+        } else if meta.path.is_ident("convention") {
+            result.convention = Some(parse_convention(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("arbitrary") {
+            result.arbitrary = true;
+            Ok(())
+        } else {
+            Err(Error::new(meta.input.span(), "unrecognized field"))
+        }
+    })?;
+    Ok(result)
+}
+
+pub fn parse_variant_attrs(attrs: &[Attribute]) -> Result<VariantAttrs, Error> {
+    let mut result = VariantAttrs::default();
+    parse_plutus_attrs(attrs, |meta| {
+        if meta.path.is_ident("constr") {
+            let value = meta.value()?;
+            let i: LitInt = value.parse()?;
+            result.variant = Some(i.base10_parse()?);
+            Ok(())
+        } else {
+            Err(Error::new(meta.input.span(), "unrecognized field"))
+        }
+    })?;
+    Ok(result)
+}
+
+pub fn parse_field_attrs(attrs: &[Attribute]) -> Result<FieldAttrs, Error> {
+    let mut result = FieldAttrs::default();
+    parse_plutus_attrs(attrs, |meta| {
+        if meta.path.is_ident("bound") {
+            result.bound = Some(parse_bound(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("range") {
+            result.range = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("len") {
+            result.len = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("skip") {
+            result.skip = true;
+            Ok(())
+        } else if meta.path.is_ident("non_empty") {
+            result.non_empty = true;
+            Ok(())
+        } else if meta.path.is_ident("flatten") {
+            result.flatten = true;
+            Ok(())
+        } else if meta.path.is_ident("pairs") {
+            result.pairs = true;
+            Ok(())
+        } else if meta.path.is_ident("index") {
+            result.index = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("convention") {
+            result.convention = Some(parse_convention(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("strategy") {
+            result.strategy = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(Error::new(meta.input.span(), "unrecognized field"))
+        }
+    })?;
+    Ok(result)
+}
+
+fn parse_plutus_attrs(
+    attrs: &[Attribute],
+    mut parse: impl FnMut(ParseNestedMeta) -> Result<(), Error>,
+) -> Result<(), Error> {
+    for a in attrs {
+        let Meta::List(list) = &a.meta else {
+            continue;
+        };
+        if !list.path.is_ident("plutus") {
+            continue;
+        }
+        list.parse_nested_meta(&mut parse)?;
+    }
+    Ok(())
+}
+
+fn parse_bound(meta: &ParseNestedMeta) -> Result<Vec<WherePredicate>, Error> {
+    let value: LitStr = meta.value()?.parse()?;
+    let predicates = value.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+    Ok(predicates.into_iter().collect())
+}
+
+fn parse_convention(meta: &ParseNestedMeta) -> Result<Convention, Error> {
+    let value: LitStr = meta.value()?.parse()?;
+    match value.value().as_str() {
+        "aiken" => Ok(Convention::Aiken),
+        "plutustx" => Ok(Convention::PlutusTx),
+        "helios" => Ok(Convention::Helios),
+        _ => Err(Error::new(
+            value.span(),
+            "unknown convention, expected \"aiken\", \"plutustx\" or \"helios\"",
+        )),
+    }
+}
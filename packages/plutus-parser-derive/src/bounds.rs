@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+
+use quote::ToTokens;
+use syn::{
+    GenericArgument, Generics, Ident, Path, PathArguments, ReturnType, Type, WherePredicate,
+};
+
+use crate::attrs::ContainerAttrs;
+use crate::fields::FieldInfo;
+
+/// Builds the generics for the impl block. Unless the container overrides it with
+/// `#[plutus(bound = "...")]`, every type parameter used by an encoded field must
+/// implement the trait, and fields can replace their own bounds the same way.
+pub fn with_bounds<'a>(
+    generics: &Generics,
+    container: &ContainerAttrs,
+    fields: impl IntoIterator<Item = &'a FieldInfo<'a>>,
+    krate: &Path,
+) -> Generics {
+    let mut generics = generics.clone();
+    let predicates = match &container.bound {
+        Some(bound) => bound.clone(),
+        None => infer_bounds(&generics, fields, krate),
+    };
+    if !predicates.is_empty() {
+        generics.make_where_clause().predicates.extend(predicates);
+    }
+    generics
+}
+
+fn infer_bounds<'a>(
+    generics: &Generics,
+    fields: impl IntoIterator<Item = &'a FieldInfo<'a>>,
+    krate: &Path,
+) -> Vec<WherePredicate> {
+    let params: HashSet<&Ident> = generics.type_params().map(|p| &p.ident).collect();
+    // params used by fields with each convention
+    let mut used = Uses::default();
+    let mut used_tx = Uses::default();
+    let mut predicates = vec![];
+    for field in fields {
+        if field.attrs.skip {
+            continue;
+        }
+        if let Some(bound) = &field.attrs.bound {
+            predicates.extend(bound.iter().cloned());
+            continue;
+        }
+        // flattened fields are encoded with AsPlutusFields, which a type parameter
+        // doesn't get from an AsPlutus bound
+        if field.attrs.flatten {
+            let mut flattened = Uses::default();
+            collect_params(field.ty, &params, &mut flattened);
+            if !flattened.is_empty() {
+                let ty = field.ty;
+                predicates.push(syn::parse_quote!(#ty: #krate::AsPlutusFields));
+            }
+            continue;
+        }
+        let used = if field.plutus_tx {
+            &mut used_tx
+        } else {
+            &mut used
+        };
+        collect_params(field.ty, &params, used);
+    }
+    // keep the bounds in declaration order so error messages are predictable
+    for param in generics.type_params().map(|p| &p.ident) {
+        if used.params.contains(&param) {
+            predicates.push(syn::parse_quote!(#param: #krate::AsPlutus));
+        }
+        if used_tx.params.contains(&param) {
+            predicates.push(syn::parse_quote!(#param: #krate::AsPlutusTx));
+        }
+    }
+    for ty in used.projections {
+        predicates.push(syn::parse_quote!(#ty: #krate::AsPlutus));
+    }
+    for ty in used_tx.projections {
+        predicates.push(syn::parse_quote!(#ty: #krate::AsPlutusTx));
+    }
+    predicates
+}
+
+/// What a field's type needs bounds on: the type parameters it uses, and associated
+/// types of them like `T::Assoc`, which need the bound instead of `T`.
+#[derive(Default)]
+struct Uses<'a> {
+    params: Vec<&'a Ident>,
+    projections: Vec<&'a Type>,
+}
+
+impl<'a> Uses<'a> {
+    fn is_empty(&self) -> bool {
+        self.params.is_empty() && self.projections.is_empty()
+    }
+
+    fn add_projection(&mut self, ty: &'a Type) {
+        let key = ty.to_token_stream().to_string();
+        if !self
+            .projections
+            .iter()
+            .any(|seen| seen.to_token_stream().to_string() == key)
+        {
+            self.projections.push(ty);
+        }
+    }
+}
+
+fn collect_params<'a>(ty: &'a Type, params: &HashSet<&'a Ident>, used: &mut Uses<'a>) {
+    match ty {
+        Type::Path(path) => {
+            if let Some(qself) = &path.qself {
+                let mut inner = Uses::default();
+                collect_params(&qself.ty, params, &mut inner);
+                if !inner.is_empty() {
+                    used.add_projection(ty);
+                    return;
+                }
+            }
+            if let Some(first) = path.path.segments.first()
+                && let Some(param) = params.get(&first.ident)
+            {
+                if path.path.segments.len() > 1 {
+                    used.add_projection(ty);
+                    return;
+                }
+                if !used.params.contains(param) {
+                    used.params.push(param);
+                }
+            }
+            if is_phantom_data(ty) {
+                return;
+            }
+            for segment in &path.path.segments {
+                match &segment.arguments {
+                    PathArguments::None => {}
+                    PathArguments::AngleBracketed(args) => {
+                        for arg in &args.args {
+                            match arg {
+                                GenericArgument::Type(ty) => collect_params(ty, params, used),
+                                GenericArgument::AssocType(assoc) => {
+                                    collect_params(&assoc.ty, params, used)
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    PathArguments::Parenthesized(args) => {
+                        for input in &args.inputs {
+                            collect_params(&input.ty, params, used);
+                        }
+                        if let ReturnType::Type(_, output) = &args.output {
+                            collect_params(output, params, used);
+                        }
+                    }
+                }
+            }
+        }
+        Type::Array(array) => collect_params(&array.elem, params, used),
+        Type::Group(group) => collect_params(&group.elem, params, used),
+        Type::Paren(paren) => collect_params(&paren.elem, params, used),
+        Type::Ptr(ptr) => collect_params(&ptr.elem, params, used),
+        Type::Reference(reference) => collect_params(&reference.elem, params, used),
+        Type::Slice(slice) => collect_params(&slice.elem, params, used),
+        Type::Tuple(tuple) => {
+            for elem in &tuple.elems {
+                collect_params(elem, params, used);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_phantom_data(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "PhantomData")
+}
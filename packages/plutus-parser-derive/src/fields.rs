@@ -0,0 +1,550 @@
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+use syn::{Error, Fields, Ident, Index, Member, Path, Type, spanned::Spanned};
+
+use crate::attrs::{ContainerAttrs, Convention, FieldAttrs, parse_field_attrs};
+
+pub struct FieldInfo<'a> {
+    pub member: Member,
+    pub binding: Ident,
+    /// The calls which add this field's segments to a `DecodeError` path.
+    pub path: TokenStream,
+    /// Where the field's first value is among the encoded fields, if they are
+    /// encoded in a constr or list.
+    pub position: Option<TokenStream>,
+    pub ty: &'a Type,
+    pub attrs: FieldAttrs,
+    pub plutus_tx: bool,
+}
+
+/// Collects the fields of a struct or variant, in the order they are encoded.
+/// `variant` is the name of the enum variant (if any), which prefixes the field
+/// names reported in errors.
+pub fn parse_fields<'a>(
+    fields: &'a Fields,
+    variant: Option<&Ident>,
+    container: &ContainerAttrs,
+) -> Result<Vec<FieldInfo<'a>>, Error> {
+    let mut fields = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let (member, field_name) = match &field.ident {
+                Some(name) => (Member::Named(name.clone()), quote! { stringify!(#name) }),
+                None => {
+                    let index = Index {
+                        index: i as u32,
+                        span: field.span(),
+                    };
+                    (Member::Unnamed(index), quote! { #i })
+                }
+            };
+            let variant = variant.map(|variant| {
+                let variant = variant.to_string();
+                quote! { .with_variant(#variant) }
+            });
+            let path = quote! { .with_field_name(#field_name) #variant };
+            let binding = Ident::new(&format!("__field{i}"), field.span());
+            let attrs = parse_field_attrs(&field.attrs)?;
+            if attrs.skip && (attrs.flatten || attrs.index.is_some()) {
+                return Err(Error::new(
+                    field.ty.span(),
+                    "skip cannot be combined with flatten or index",
+                ));
+            }
+            if attrs.flatten && attrs.pairs {
+                return Err(Error::new(
+                    field.ty.span(),
+                    "pairs cannot be combined with flatten",
+                ));
+            }
+            if attrs.flatten && attrs.convention.is_some() {
+                return Err(Error::new(
+                    field.ty.span(),
+                    "convention cannot be combined with flatten",
+                ));
+            }
+            if attrs.convention == Some(Convention::Helios) {
+                return Err(Error::new(
+                    field.ty.span(),
+                    "the helios convention can only be used on types",
+                ));
+            }
+            if attrs.flatten && container.convention == Some(Convention::Helios) {
+                return Err(Error::new(
+                    field.ty.span(),
+                    "flatten is not supported with the helios convention",
+                ));
+            }
+            let convention = attrs
+                .convention
+                .or(container.convention)
+                .unwrap_or(Convention::Aiken);
+            Ok(FieldInfo {
+                member,
+                binding,
+                path,
+                position: None,
+                ty: &field.ty,
+                attrs,
+                plutus_tx: convention == Convention::PlutusTx,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    sort_by_index(&mut fields, container.require_index)?;
+    let krate = container.krate();
+    let mut position = quote! { 0usize };
+    for field in fields.iter_mut().filter(|f| !f.attrs.skip) {
+        field.position = Some(position.clone());
+        let ty = field.ty;
+        position = if field.attrs.flatten {
+            quote! { #position + <#ty as #krate::AsPlutusFields>::FIELD_COUNT }
+        } else {
+            quote! { #position + 1 }
+        };
+    }
+    Ok(fields)
+}
+
+/// Orders the fields by their `#[plutus(index = N)]` attributes. Either every
+/// encoded field is pinned to a position or none are, and the positions must
+/// run from 0 with no gaps or duplicates. A flattened field takes one index,
+/// and its own fields follow each other from there.
+fn sort_by_index(fields: &mut [FieldInfo], require_index: bool) -> Result<(), Error> {
+    let mut indices = vec![];
+    for field in wire_fields(fields) {
+        match &field.attrs.index {
+            Some(index) => indices.push((index.base10_parse::<usize>()?, index)),
+            None if require_index => {
+                return Err(Error::new(field.ty.span(), "field must have an index"));
+            }
+            None => {}
+        }
+    }
+    if indices.is_empty() {
+        return Ok(());
+    }
+    let count = wire_fields(fields).count();
+    if indices.len() != count {
+        let field = wire_fields(fields)
+            .find(|f| f.attrs.index.is_none())
+            .unwrap();
+        return Err(Error::new(
+            field.ty.span(),
+            "field must have an index, because other fields have one",
+        ));
+    }
+    let mut seen = HashSet::new();
+    for (index, lit) in &indices {
+        if !seen.insert(*index) {
+            return Err(Error::new(lit.span(), format!("duplicate index {index}")));
+        }
+    }
+    if let Some((index, lit)) = indices.iter().find(|(index, _)| *index >= count) {
+        let missing = (0..count).find(|i| !seen.contains(i)).unwrap();
+        return Err(Error::new(
+            lit.span(),
+            format!("index {index} leaves a gap, index {missing} is missing"),
+        ));
+    }
+    fields.sort_by_key(|f| {
+        f.attrs
+            .index
+            .as_ref()
+            .and_then(|i| i.base10_parse::<usize>().ok())
+    });
+    Ok(())
+}
+
+impl FieldInfo<'_> {
+    pub fn has_constraints(&self) -> bool {
+        self.attrs.range.is_some() || self.attrs.len.is_some() || self.attrs.non_empty
+    }
+
+    /// The calls which add this field's segments and position to a `DecodeError`.
+    fn locate(&self) -> TokenStream {
+        let FieldInfo { path, position, .. } = self;
+        match position {
+            None => path.clone(),
+            Some(position) if self.attrs.flatten => {
+                quote! { #path.shift_position(#position) }
+            }
+            Some(position) => quote! { #path.with_position(#position) },
+        }
+    }
+
+    fn decode_fn(&self, krate: &Path) -> TokenStream {
+        if self.plutus_tx {
+            quote! { #krate::AsPlutusTx::from_plutus_tx }
+        } else {
+            quote! { #krate::AsPlutus::from_plutus }
+        }
+    }
+
+    fn validate_fn(&self, krate: &Path) -> TokenStream {
+        let ty = self.ty;
+        if self.plutus_tx {
+            quote! { <#ty as #krate::AsPlutusTx>::validate_plutus_tx }
+        } else {
+            quote! { <#ty as #krate::AsPlutus>::validate_plutus }
+        }
+    }
+
+    fn check_fn(&self, krate: &Path) -> TokenStream {
+        let ty = self.ty;
+        if self.plutus_tx {
+            quote! { <#ty as #krate::AsPlutusTx>::check_to_plutus_tx }
+        } else {
+            quote! { <#ty as #krate::AsPlutus>::check_to_plutus }
+        }
+    }
+
+    fn encode_fn(&self, krate: &Path) -> TokenStream {
+        if self.plutus_tx {
+            quote! { #krate::AsPlutusTx::to_plutus_tx }
+        } else {
+            quote! { #krate::AsPlutus::to_plutus }
+        }
+    }
+}
+
+/// Checks the field's declarative constraints against `value` (a reference to the field),
+/// returning an `error` (`DecodeError` or `EncodeError`) with `path` added on failure.
+fn constraints(field: &FieldInfo, error: TokenStream, path: TokenStream) -> TokenStream {
+    let mut checks = quote! {};
+    if let Some(range) = &field.attrs.range {
+        let range_str = range.to_token_stream().to_string().replace(' ', "");
+        checks.extend(quote! {
+            if !(#range).contains(value) {
+                return Err(#error::validation(format!(
+                    "value {} is not in range {}",
+                    value, #range_str
+                ))
+                #path);
+            }
+        });
+    }
+    if let Some(len) = &field.attrs.len {
+        checks.extend(quote! {
+            if value.len() != #len {
+                return Err(#error::validation(format!(
+                    "expected length {}, found {}",
+                    #len,
+                    value.len()
+                ))
+                #path);
+            }
+        });
+    }
+    if field.attrs.non_empty {
+        checks.extend(quote! {
+            if value.is_empty() {
+                return Err(#error::validation("value is empty")#path);
+            }
+        });
+    }
+    checks
+}
+
+/// The fields which are actually present in the encoded data.
+pub fn wire_fields<'a, 'b>(fields: &'a [FieldInfo<'b>]) -> impl Iterator<Item = &'a FieldInfo<'b>> {
+    fields.iter().filter(|f| !f.attrs.skip)
+}
+
+/// The number of values the fields occupy in the encoded data.
+pub fn field_count(krate: &Path, fields: &[FieldInfo]) -> TokenStream {
+    let plain = wire_fields(fields).filter(|f| !f.attrs.flatten).count();
+    let flattened = wire_fields(fields).filter(|f| f.attrs.flatten).map(|f| {
+        let ty = f.ty;
+        quote! { + <#ty as #krate::AsPlutusFields>::FIELD_COUNT }
+    });
+    quote! { #plain #(#flattened)* }
+}
+
+/// Decodes every field from the `fields` iterator (a `&mut impl Iterator`) into its binding, in order.
+pub fn decode_fields(krate: &Path, fields: &[FieldInfo]) -> TokenStream {
+    let decodes = wire_fields(fields).map(|f| {
+        let FieldInfo { binding, ty, .. } = f;
+        let path = f.locate();
+        let value = if f.attrs.flatten {
+            quote! {
+                <#ty as #krate::AsPlutusFields>::from_plutus_fields(fields).map_err(|e| e #path)?
+            }
+        } else {
+            let from_plutus = f.decode_fn(krate);
+            let into_inner = f.attrs.pairs.then(|| {
+                quote! { .map(#krate::Pairs::into_inner) }
+            });
+            quote! {
+                #from_plutus(#krate::next_field(fields)?)
+                    #into_inner
+                    .map_err(|e| e #path)?
+            }
+        };
+        if f.has_constraints() {
+            let checks = constraints(f, quote! { #krate::DecodeError }, path);
+            quote! {
+                let #binding: #ty = {
+                    let value: #ty = #value;
+                    {
+                        let value = &value;
+                        #checks
+                    }
+                    value
+                };
+            }
+        } else {
+            quote! {
+                let #binding: #ty = #value;
+            }
+        }
+    });
+    quote! {
+        #(#decodes)*
+    }
+}
+
+/// Checks every field from the `fields` iterator (a `&mut std::slice::Iter<PlutusData>`)
+/// in order, pushing what goes wrong onto the `errors` vector.
+pub fn validate_fields(krate: &Path, fields: &[FieldInfo]) -> TokenStream {
+    let checks = wire_fields(fields).map(|f| {
+        let ty = f.ty;
+        let path = f.locate();
+        if f.attrs.flatten {
+            return quote! {
+                errors.extend(
+                    <#ty as #krate::AsPlutusFields>::validate_plutus_fields(fields)
+                        .into_iter()
+                        .map(|e| e #path),
+                );
+            };
+        }
+        let from_plutus = f.decode_fn(krate);
+        // pairs fields are checked by decoding them, since `ty` is the unwrapped vector
+        let field_errors = if f.attrs.pairs {
+            quote! {
+                match #from_plutus(field.clone()).map(#krate::Pairs::into_inner) {
+                    Ok::<#ty, _>(_) => vec![],
+                    Err(error) => vec![error],
+                }
+            }
+        } else {
+            let validate = f.validate_fn(krate);
+            quote! { #validate(field) }
+        };
+        let constraints = f.has_constraints().then(|| {
+            let checks = constraints(f, quote! { #krate::DecodeError }, path.clone());
+            let into_inner = f.attrs.pairs.then(|| {
+                quote! { .map(#krate::Pairs::into_inner) }
+            });
+            quote! {
+                if field_errors.is_empty() {
+                    let checked = (|| -> Result<(), #krate::DecodeError> {
+                        let value: #ty = #from_plutus(field.clone()) #into_inner ?;
+                        let value = &value;
+                        #checks
+                        Ok(())
+                    })();
+                    if let Err(error) = checked {
+                        errors.push(error);
+                    }
+                }
+            }
+        });
+        quote! {
+            match fields.next() {
+                Some(field) => {
+                    let field_errors = #field_errors;
+                    #constraints
+                    errors.extend(field_errors.into_iter().map(|e| e #path));
+                }
+                None => errors.push(#krate::DecodeError::custom("missing field")),
+            }
+        }
+    });
+    quote! {
+        #(#checks)*
+    }
+}
+
+/// Checks the fields in the `fields` slice, after checking that it has the right
+/// number of values, and returns the errors. `mismatch` builds the error from
+/// `expected` and `actual`.
+pub fn validate_field_slice(
+    krate: &Path,
+    fields: &[FieldInfo],
+    mismatch: TokenStream,
+) -> TokenStream {
+    let count = field_count(krate, fields);
+    let checks = validate_fields(krate, fields);
+    let iter = (wire_fields(fields).count() > 0).then(|| {
+        quote! {
+            let fields = &mut fields.iter();
+        }
+    });
+    quote! {
+        let expected = #count;
+        let actual = fields.len();
+        if actual != expected {
+            return vec![#mismatch];
+        }
+        #[allow(unused_mut)]
+        let mut errors: Vec<#krate::DecodeError> = vec![];
+        #iter
+        #checks
+        errors
+    }
+}
+
+/// Decodes the fields out of the `fields` vector, after checking that it has the
+/// right number of values. `mismatch` builds the error from `expected` and `actual`.
+pub fn decode_field_vec(krate: &Path, fields: &[FieldInfo], mismatch: TokenStream) -> TokenStream {
+    let count = field_count(krate, fields);
+    let decodes = decode_fields(krate, fields);
+    let into_iter = (wire_fields(fields).count() > 0).then(|| {
+        quote! {
+            let fields = &mut fields.into_iter();
+        }
+    });
+    quote! {
+        let expected = #count;
+        let actual = fields.len();
+        if actual != expected {
+            return Err(#mismatch);
+        }
+        #into_iter
+        #decodes
+    }
+}
+
+/// Checks that every bound field (a reference) can be encoded, returning the first error.
+pub fn check_fields(krate: &Path, fields: &[FieldInfo]) -> TokenStream {
+    let checks = wire_fields(fields).map(|f| {
+        let FieldInfo {
+            binding, ty, path, ..
+        } = f;
+        let check = if f.attrs.flatten {
+            quote! {
+                <#ty as #krate::AsPlutusFields>::check_to_plutus_fields(#binding)
+                    .map_err(|e| e #path)?;
+            }
+        } else if f.attrs.pairs {
+            // pairs fields hold a plain vector, so check the entries like `Pairs` would
+            let check = if f.plutus_tx {
+                quote! { #krate::AsPlutusTx::check_to_plutus_tx }
+            } else {
+                quote! { #krate::AsPlutus::check_to_plutus }
+            };
+            quote! {
+                for (index, (key, value)) in #binding.iter().enumerate() {
+                    #check(key).map_err(|e| e.with_map_key(index) #path)?;
+                    #check(value).map_err(|e| e.with_map_value(index) #path)?;
+                }
+            }
+        } else {
+            let check_fn = f.check_fn(krate);
+            quote! {
+                #check_fn(#binding).map_err(|e| e #path)?;
+            }
+        };
+        let constraints = f.has_constraints().then(|| {
+            let checks = constraints(f, quote! { #krate::EncodeError }, path.clone());
+            quote! {
+                {
+                    let value = #binding;
+                    #checks
+                }
+            }
+        });
+        quote! {
+            #check
+            #constraints
+        }
+    });
+    quote! {
+        #(#checks)*
+    }
+}
+
+/// Builds `Ctor { field: binding, ... }` out of the decoded bindings.
+pub fn construct(ctor: TokenStream, fields: &[FieldInfo]) -> TokenStream {
+    let assignments = fields.iter().map(|f| {
+        let FieldInfo {
+            member, binding, ..
+        } = f;
+        if f.attrs.skip {
+            quote! { #member: ::core::default::Default::default(), }
+        } else {
+            quote! { #member: #binding, }
+        }
+    });
+    quote! {
+        #ctor {
+            #(#assignments)*
+        }
+    }
+}
+
+/// Builds the `Ctor { field: binding, ... }` pattern to destructure a value.
+pub fn destructure(ctor: TokenStream, fields: &[FieldInfo]) -> TokenStream {
+    let patterns = fields.iter().map(|f| {
+        let FieldInfo {
+            member, binding, ..
+        } = f;
+        if f.attrs.skip {
+            quote! { #member: _, }
+        } else {
+            quote! { #member: #binding, }
+        }
+    });
+    quote! {
+        #ctor {
+            #(#patterns)*
+        }
+    }
+}
+
+/// Pushes every bound field onto the `fields` vector (a `&mut Vec<PlutusData>`), in order.
+pub fn push_fields(krate: &Path, fields: &[FieldInfo]) -> TokenStream {
+    let pushes = wire_fields(fields).map(|f| {
+        let FieldInfo { binding, ty, .. } = f;
+        if f.attrs.flatten {
+            quote! {
+                <#ty as #krate::AsPlutusFields>::to_plutus_fields(#binding, fields);
+            }
+        } else {
+            let to_plutus = f.encode_fn(krate);
+            let value = if f.attrs.pairs {
+                quote! { #krate::Pairs::from(#binding) }
+            } else {
+                quote! { #binding }
+            };
+            quote! {
+                fields.push(#to_plutus(#value));
+            }
+        }
+    });
+    quote! {
+        #(#pushes)*
+    }
+}
+
+/// Encodes every bound field into a new `encoded` vector.
+pub fn encode_fields(krate: &Path, fields: &[FieldInfo]) -> TokenStream {
+    if wire_fields(fields).count() == 0 {
+        return quote! {
+            let encoded: Vec<#krate::PlutusData> = Vec::new();
+        };
+    }
+    let count = field_count(krate, fields);
+    let pushes = push_fields(krate, fields);
+    quote! {
+        let mut encoded: Vec<#krate::PlutusData> = Vec::with_capacity(#count);
+        {
+            let fields = &mut encoded;
+            #pushes
+        }
+    }
+}
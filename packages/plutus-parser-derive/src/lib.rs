@@ -3,376 +3,440 @@ use std::collections::HashSet;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::{quote, quote_spanned};
-use syn::{
-    Attribute, Data, DeriveInput, Error, Fields, Ident, LitInt, Meta, parse_macro_input,
-    spanned::Spanned,
+use syn::{Data, DeriveInput, Error, parse_macro_input, spanned::Spanned};
+
+use crate::attrs::{
+    ContainerAttrs, Convention, DataFormat, parse_container_attrs, parse_variant_attrs,
+};
+use crate::fields::{
+    FieldInfo, check_fields, construct, decode_field_vec, decode_fields, destructure,
+    encode_fields, field_count, parse_fields, push_fields, validate_field_slice, validate_fields,
+    wire_fields,
 };
 
+mod arbitrary;
+mod attrs;
+mod bounds;
+mod fields;
+
 #[proc_macro_derive(AsPlutus, attributes(plutus))]
 pub fn derive_as_plutus(input: TokenStream) -> TokenStream {
     let input: DeriveInput = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
     let name = &input.ident;
+    let container = parse_container_attrs(&input.attrs)?;
+    let krate = container.krate();
+    let type_name = name.to_string();
 
-    let implementation = match &input.data {
+    let mut all_fields: Vec<FieldInfo>;
+    let mut fields_impl = None;
+    let mut arbitrary_strategy = None;
+    let check_plutus;
+    let (from_plutus, to_plutus, validate_plutus) = match &input.data {
         Data::Struct(s) => {
-            let format = match get_format(&input.attrs) {
-                Ok(Some(f)) => f,
-                Ok(None) => DataFormat::Constr { variant: 0 },
-                Err(e) => {
-                    return e.to_compile_error().into();
+            all_fields = parse_fields(&s.fields, None, &container)?;
+            let format = match (&container.format, container.convention) {
+                (Some(_), Some(Convention::Helios)) => {
+                    return Err(Error::new(
+                        Span::call_site(),
+                        "list and constr cannot be combined with the helios convention",
+                    ));
                 }
+                (Some(format), _) => format,
+                // Helios encodes a struct with one field as that field, and any other struct as a list
+                (None, Some(Convention::Helios)) if wire_fields(&all_fields).count() == 1 => {
+                    &DataFormat::Bare
+                }
+                (None, Some(Convention::Helios)) => &DataFormat::List,
+                (None, _) => &DataFormat::Constr { variant: 0 },
             };
-            let from_plutus;
-            let to_plutus;
-            match &s.fields {
-                Fields::Named(named) => {
-                    let names: Vec<_> = named
-                        .named
-                        .iter()
-                        .map(|n| n.ident.as_ref().unwrap())
-                        .collect();
-                    let assignments = names.iter().map(|n| {
-                        quote! {
-                            #n: plutus_parser::AsPlutus::from_plutus(#n).map_err(|e| e.with_field_name(stringify!(#n)))?,
-                        }
-                    });
-                    let casts: Vec<_> = names
-                        .iter()
-                        .map(|n| {
-                            quote! {
-                                self.#n.to_plutus(),
-                            }
-                        })
-                        .collect();
-
-                    match format {
-                        DataFormat::Constr { variant } => {
-                            from_plutus = quote! {
-                                let (variant, fields) = plutus_parser::parse_constr(data)?;
-                                if variant == #variant {
-                                    let [#(#names),*] = plutus_parser::parse_variant(variant, fields)?;
-                                    return Ok(Self {
-                                        #(#assignments)*
-                                    });
-                                }
-                                Err(plutus_parser::DecodeError::unexpected_variant(variant))
-                            };
-                            to_plutus = quote! {
-                                plutus_parser::create_constr(#variant, vec![
-                                    #(#casts)*
-                                ])
-                            };
-                        }
-                        DataFormat::List => {
-                            from_plutus = quote! {
-                                let [#(#names),*] = plutus_parser::parse_tuple(data)?;
-                                return Ok(Self {
-                                    #(#assignments)*
-                                });
-                            };
-                            to_plutus = quote! {
-                                plutus_parser::create_array(vec![
-                                    #(#casts)*
-                                ])
-                            };
-                        }
-                    }
+            if let DataFormat::Bare = format {
+                // the field is the whole value, so it has no position of its own
+                for field in &mut all_fields {
+                    field.position = None;
                 }
-                Fields::Unit => match format {
-                    DataFormat::Constr { variant } => {
-                        from_plutus = quote! {
-                            let (variant, fields) = plutus_parser::parse_constr(data)?;
+            }
+            if container.arbitrary {
+                arbitrary_strategy = Some(arbitrary::fields_strategy(
+                    &krate,
+                    name,
+                    quote! { Self },
+                    &all_fields,
+                )?);
+            }
+            let count = field_count(&krate, &all_fields);
+            let decodes = decode_fields(&krate, &all_fields);
+            let value = construct(quote! { Self }, &all_fields);
+            let pattern = destructure(quote! { Self }, &all_fields);
+            let pushes = push_fields(&krate, &all_fields);
+            let checks = validate_fields(&krate, &all_fields);
+            let encode_checks = check_fields(&krate, &all_fields);
+            check_plutus = quote! {
+                let #pattern = self;
+                #encode_checks
+            };
+
+            let (from_plutus, to_plutus, validate_plutus) = match format {
+                DataFormat::Constr { variant } => {
+                    let mismatch = quote! {
+                        #krate::DecodeError::wrong_variant_field_count(variant, expected, actual)
+                    };
+                    let decodes = decode_field_vec(&krate, &all_fields, mismatch.clone());
+                    let checks = validate_field_slice(&krate, &all_fields, mismatch);
+                    let unexpected_variant = quote! {
+                        #krate::DecodeError::unexpected_variant(variant)
+                            .with_type_name(#type_name)
+                            .with_expected_variants(&[(#variant, #type_name)])
+                    };
+                    (
+                        quote! {
+                            let (variant, fields) = #krate::parse_constr(data)
+                                .map_err(|e| e.with_type_name(#type_name))?;
                             if variant == #variant {
-                                let [] = plutus_parser::parse_variant(variant, fields)?;
-                                return Ok(Self);
-                            }
-                            Err(plutus_parser::DecodeError::unexpected_variant(variant))
-                        };
-                        to_plutus = quote! {
-                            plutus_parser::create_constr(#variant, vec![])
-                        }
-                    }
-                    DataFormat::List => {
-                        from_plutus = quote! {
-                            let [] = plutus_parser::parse_tuple(data)?;
-                            return Ok(Self);
-                        };
-                        to_plutus = quote! {
-                            plutus_parser::create_array(vec![])
-                        }
-                    }
-                },
-                Fields::Unnamed(fields) => {
-                    let names: Vec<_> = fields
-                        .unnamed
-                        .iter()
-                        .enumerate()
-                        .map(|(i, field)| {
-                            let name = format!("f{i}");
-                            let span = field.span();
-                            Ident::new(&name, span)
-                        })
-                        .collect();
-                    let assignments: Vec<_> = names
-                        .iter()
-                        .enumerate()
-                        .map(|(i, n)| {
-                            quote! {
-                                plutus_parser::AsPlutus::from_plutus(#n).map_err(|e| e.with_field_name(#i))?,
+                                #decodes
+                                return Ok(#value);
                             }
-                        })
-                        .collect();
-                    let casts: Vec<_> = names
-                        .iter()
-                        .map(|n| {
-                            quote! {
-                                #n.to_plutus(),
-                            }
-                        })
-                        .collect();
-                    match format {
-                        DataFormat::Constr { variant } => {
-                            from_plutus = quote! {
-                                let (variant, fields) = plutus_parser::parse_constr(data)?;
-                                if variant == #variant {
-                                    let [#(#names),*] = plutus_parser::parse_variant(variant, fields)?;
-                                    return Ok(Self(#(#assignments)*));
-                                }
-                                Err(plutus_parser::DecodeError::unexpected_variant(variant))
+                            Err(#unexpected_variant)
+                        },
+                        quote! {
+                            let mut fields = Vec::with_capacity(#count);
+                            #krate::AsPlutusFields::to_plutus_fields(self, &mut fields);
+                            #krate::create_constr(#variant, fields)
+                        },
+                        quote! {
+                            let (variant, fields) = match #krate::parse_constr_ref(data) {
+                                Ok(constr) => constr,
+                                Err(error) => return vec![error.with_type_name(#type_name)],
                             };
-                            to_plutus = quote! {
-                                let Self(#(#names),*) = self;
-                                plutus_parser::create_constr(#variant, vec![
-                                    #(#casts)*
-                                ])
+                            if variant != #variant {
+                                return vec![#unexpected_variant];
                             }
-                        }
-                        DataFormat::List => {
-                            from_plutus = quote! {
-                                let [#(#names),*] = plutus_parser::parse_tuple(data)?;
-                                return Ok(Self(#(#assignments)*));
+                            #checks
+                        },
+                    )
+                }
+                DataFormat::Bare => (
+                    quote! {
+                        let fields = &mut ::core::iter::once(data);
+                        #decodes
+                        Ok(#value)
+                    },
+                    quote! {
+                        let mut fields = Vec::with_capacity(1);
+                        #krate::AsPlutusFields::to_plutus_fields(self, &mut fields);
+                        fields.remove(0)
+                    },
+                    quote! {
+                        let fields = &mut ::core::slice::from_ref(data).iter();
+                        let mut errors = vec![];
+                        #checks
+                        errors
+                    },
+                ),
+                DataFormat::List => {
+                    let mismatch = quote! {
+                        #krate::DecodeError::wrong_tuple_field_count(expected, actual)
+                    };
+                    let decodes = decode_field_vec(&krate, &all_fields, mismatch.clone());
+                    let checks = validate_field_slice(&krate, &all_fields, mismatch);
+                    (
+                        quote! {
+                            let fields = #krate::parse_array(data)
+                                .map_err(|e| e.with_type_name(#type_name))?;
+                            #decodes
+                            Ok(#value)
+                        },
+                        quote! {
+                            let mut fields = Vec::with_capacity(#count);
+                            #krate::AsPlutusFields::to_plutus_fields(self, &mut fields);
+                            #krate::create_array(fields)
+                        },
+                        quote! {
+                            let fields = match #krate::parse_array_ref(data) {
+                                Ok(fields) => fields,
+                                Err(error) => return vec![error.with_type_name(#type_name)],
                             };
-                            to_plutus = quote! {
-                                let Self(#(#names),*) = self;
-                                plutus_parser::create_array(vec![
-                                    #(#casts)*
-                                ])
-                            }
+                            #checks
+                        },
+                    )
+                }
+            };
+
+            let from_plutus_fields = validated(
+                &krate,
+                &container,
+                quote! {
+                    #decodes
+                    Ok(#value)
+                },
+            );
+            let validate_plutus_fields = match &container.validate {
+                // the hook needs the whole value, so decode it once every field is valid
+                Some(_) => quote! {
+                    let start = fields.clone();
+                    #[allow(unused_mut)]
+                    let mut errors: Vec<#krate::DecodeError> = vec![];
+                    #checks
+                    if errors.is_empty() {
+                        let mut values = start.take(#count).cloned();
+                        if let Err(error) = Self::from_plutus_fields(&mut values) {
+                            errors.push(error);
                         }
                     }
-                }
+                    errors
+                },
+                None => quote! {
+                    #[allow(unused_mut)]
+                    let mut errors: Vec<#krate::DecodeError> = vec![];
+                    #checks
+                    errors
+                },
             };
+            fields_impl = Some(quote! {
+                const FIELD_COUNT: usize = #count;
 
-            quote! {
-                fn from_plutus(data: plutus_parser::PlutusData) -> Result<Self, plutus_parser::DecodeError> {
-                    #from_plutus
+                fn from_plutus_fields(
+                    fields: &mut impl Iterator<Item = #krate::PlutusData>,
+                ) -> Result<Self, #krate::DecodeError> {
+                    #from_plutus_fields
                 }
 
-                fn to_plutus(self) -> plutus_parser::PlutusData {
-                    #to_plutus
+                fn to_plutus_fields(self, fields: &mut Vec<#krate::PlutusData>) {
+                    let #pattern = self;
+                    #pushes
                 }
-            }
+
+                #[allow(unused_variables)]
+                fn validate_plutus_fields(
+                    fields: &mut ::core::slice::Iter<'_, #krate::PlutusData>,
+                ) -> Vec<#krate::DecodeError> {
+                    #validate_plutus_fields
+                }
+
+                fn check_to_plutus_fields(&self) -> Result<(), #krate::EncodeError> {
+                    <Self as #krate::AsPlutus>::check_to_plutus(self)
+                }
+            });
+
+            (from_plutus, to_plutus, validate_plutus)
         }
         Data::Enum(e) => {
+            if container.format.is_some() {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "list and constr are not supported on enums",
+                ));
+            }
             let mut from_plutus = quote! {
-                let (variant, fields) = plutus_parser::parse_constr(data)?;
+                let (variant, fields) = #krate::parse_constr(data)
+                    .map_err(|e| e.with_type_name(#type_name))?;
             };
             let mut to_plutus = quote! {};
+            let mut validate_plutus = quote! {
+                let (variant, fields) = match #krate::parse_constr_ref(data) {
+                    Ok(constr) => constr,
+                    Err(error) => return vec![error.with_type_name(#type_name)],
+                };
+            };
             let mut seen_variants = HashSet::new();
+            let mut variant_fields = vec![];
+            let mut expected = vec![];
+            let mut check_arms = quote! {};
+            let mut arbitrary_variants = vec![];
             for variant in &e.variants {
                 let name = &variant.ident;
-                let n = match get_variant(&variant.attrs) {
-                    Ok(Some(variant)) => variant,
-                    Ok(None) => seen_variants.len() as u64,
-                    Err(e) => {
-                        return e.to_compile_error().into();
-                    }
+                let n = match parse_variant_attrs(&variant.attrs)?.variant {
+                    Some(variant) => variant,
+                    None => seen_variants.len() as u64,
                 };
                 seen_variants.insert(n);
-                let (from_clause, to_clause) = match &variant.fields {
-                    Fields::Named(named) => {
-                        let names: Vec<_> = named
-                            .named
-                            .iter()
-                            .map(|n| n.ident.as_ref().unwrap())
-                            .collect();
-                        let assignments = names.iter().map(|n| {
-                            let field_name = format!("::{name}.{n}");
-                            quote! {
-                                #n: plutus_parser::AsPlutus::from_plutus(#n).map_err(|e| e.with_field_name(#field_name))?,
-                            }
-                        });
-                        let casts: Vec<_> = names
-                            .iter()
-                            .map(|n| {
-                                quote! {
-                                    #n.to_plutus(),
-                                }
-                            })
-                            .collect();
-                        (
-                            quote! {
-                                let [#(#names),*] = plutus_parser::parse_variant(variant, fields)?;
-                                return Ok(Self::#name {
-                                    #(#assignments)*
-                                });
-                            },
-                            quote! {
-                                Self::#name { #(#names),* } => plutus_parser::create_constr(#n, vec![
-                                    #(#casts)*
-                                ]),
-                            },
-                        )
-                    }
-                    Fields::Unit => (
-                        quote! {
-                            let [] = plutus_parser::parse_variant(variant, fields)?;
-                            return Ok(Self::#name);
-                        },
-                        quote! {
-                            Self::#name => plutus_parser::create_constr(#n, vec![]),
-                        },
-                    ),
-                    Fields::Unnamed(fields) => {
-                        let names: Vec<_> = fields
-                            .unnamed
-                            .iter()
-                            .enumerate()
-                            .map(|(i, field)| {
-                                let name = format!("f{i}");
-                                let span = field.span();
-                                Ident::new(&name, span)
-                            })
-                            .collect();
-                        let assignments: Vec<_> = names
-                            .iter()
-                            .enumerate()
-                            .map(|(i, n)| {
-                                let field_name = format!("::{name}.{i}");
-                                quote! {
-                                    plutus_parser::AsPlutus::from_plutus(#n).map_err(|e| e.with_field_name(#field_name))?,
-                                }
-                            })
-                            .collect();
-                        let casts: Vec<_> = names
-                            .iter()
-                            .map(|n| {
-                                quote! {
-                                    #n.to_plutus(),
-                                }
-                            })
-                            .collect();
-                        (
-                            quote! {
-                                let [#(#names),*] = plutus_parser::parse_variant(variant, fields)?;
-                                return Ok(Self::#name(#(#assignments)*));
-                            },
-                            quote! {
-                                Self::#name(#(#names),*) => plutus_parser::create_constr(#n, vec![
-                                    #(#casts)*
-                                ]),
-                            },
-                        )
-                    }
+                expected.push((n, name.to_string()));
+                let fields = parse_fields(&variant.fields, Some(name), &container)?;
+                if container.arbitrary {
+                    arbitrary_variants.push((
+                        arbitrary::fields_strategy(
+                            &krate,
+                            &input.ident,
+                            quote! { Self::#name },
+                            &fields,
+                        )?,
+                        arbitrary::recursive_fields(&input.ident, &fields),
+                    ));
+                }
+                let mismatch = quote! {
+                    #krate::DecodeError::wrong_variant_field_count(variant, expected, actual)
                 };
+                let decodes = decode_field_vec(&krate, &fields, mismatch.clone());
+                let checks = validate_field_slice(&krate, &fields, mismatch);
+                let value = construct(quote! { Self::#name }, &fields);
+                let pattern = destructure(quote! { Self::#name }, &fields);
+                let encodes = encode_fields(&krate, &fields);
+                let encode_checks = check_fields(&krate, &fields);
                 from_plutus.extend(quote_spanned! { variant.span() =>
                     if variant == #n {
-                        #from_clause
+                        #decodes
+                        return Ok(#value);
+                    }
+                });
+                validate_plutus.extend(quote_spanned! { variant.span() =>
+                    if variant == #n {
+                        return { #checks };
                     }
                 });
                 to_plutus.extend(quote_spanned! {variant.span() =>
-                    #to_clause
+                    #pattern => {
+                        #encodes
+                        #krate::create_constr(#n, encoded)
+                    }
                 });
+                check_arms.extend(quote_spanned! {variant.span() =>
+                    #pattern => {
+                        #encode_checks
+                    }
+                });
+                variant_fields.extend(fields);
+            }
+            all_fields = variant_fields;
+            if container.arbitrary {
+                arbitrary_strategy =
+                    Some(arbitrary::variants_strategy(&krate, arbitrary_variants)?);
             }
+            expected.sort();
+            let expected = expected.iter().map(|(n, name)| quote! { (#n, #name) });
+            let unexpected_variant = quote! {
+                #krate::DecodeError::unexpected_variant(variant)
+                    .with_type_name(#type_name)
+                    .with_expected_variants(&[#(#expected),*])
+            };
             from_plutus.extend(quote! {
-                Err(plutus_parser::DecodeError::unexpected_variant(variant))
+                Err(#unexpected_variant)
+            });
+            validate_plutus.extend(quote! {
+                vec![#unexpected_variant]
             });
 
-            quote! {
-                fn from_plutus(data: plutus_parser::PlutusData) -> Result<Self, plutus_parser::DecodeError> {
-                    #from_plutus
+            let to_plutus = quote! {
+                match self {
+                    #to_plutus
                 }
-
-                fn to_plutus(self) -> plutus_parser::PlutusData {
+            };
+            // an empty enum has no values to check, but a reference to one isn't empty
+            check_plutus = if e.variants.is_empty() {
+                quote! { match *self {} }
+            } else {
+                quote! {
                     match self {
-                        #to_plutus
+                        #check_arms
                     }
                 }
-            }
+            };
+            (from_plutus, to_plutus, validate_plutus)
         }
         _ => {
-            return Error::new(Span::call_site(), "Unsupported type")
-                .into_compile_error()
-                .into();
+            return Err(Error::new(Span::call_site(), "Unsupported type"));
         }
     };
 
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
-    let expanded = quote! {
-        impl #impl_generics plutus_parser::AsPlutus for #name #ty_generics #where_clause {
-            #implementation
-        }
+    let from_plutus = validated(&krate, &container, from_plutus);
+    let validate_plutus = match &container.validate {
+        // the hook needs the whole value, so decode it once every field is valid
+        Some(_) => quote! {
+            let errors = (|| -> Vec<#krate::DecodeError> { #validate_plutus })();
+            if errors.is_empty() {
+                return #krate::validate_by_decoding::<Self>(data);
+            }
+            errors
+        },
+        None => validate_plutus,
     };
 
-    TokenStream::from(expanded)
-}
+    let check_plutus = match &container.validate {
+        Some(validate) => quote! {
+            #check_plutus
+            #validate(self).map_err(#krate::EncodeError::validation)?;
+            Ok(())
+        },
+        None => quote! {
+            #check_plutus
+            Ok(())
+        },
+    };
 
-enum DataFormat {
-    List,
-    Constr { variant: u64 },
-}
+    let arbitrary_impl = arbitrary_strategy
+        .map(|strategy| arbitrary::impl_arbitrary(&krate, input, &container, strategy))
+        .transpose()?;
 
-fn get_format(attrs: &[Attribute]) -> Result<Option<DataFormat>, Error> {
-    for a in attrs {
-        let Meta::List(list) = &a.meta else {
-            continue;
-        };
-        if !list.path.is_ident("plutus") {
-            continue;
+    let generics = bounds::with_bounds(&input.generics, &container, &all_fields, &krate);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let fields_impl = fields_impl.map(|fields_impl| {
+        quote! {
+            impl #impl_generics #krate::AsPlutusFields for #name #ty_generics #where_clause {
+                #fields_impl
+            }
         }
-        let mut format = None;
-        list.parse_nested_meta(|meta| {
-            if meta.path.is_ident("list") {
-                format = Some(DataFormat::List);
-                Ok(())
-            } else if meta.path.is_ident("constr") {
-                if !meta.input.is_empty() {
-                    let value = meta.value()?;
-                    let i: LitInt = value.parse()?;
-                    format = Some(DataFormat::Constr {
-                        variant: i.base10_parse()?,
-                    });
-                }
-                Ok(())
-            } else {
-                Err(Error::new(meta.input.span(), "unrecognized field"))
+    });
+    Ok(quote! {
+        impl #impl_generics #krate::AsPlutus for #name #ty_generics #where_clause {
+            fn from_plutus(data: #krate::PlutusData) -> Result<Self, #krate::DecodeError> {
+                #krate::grow_stack(move || { #from_plutus })
+            }
+
+            fn to_plutus(self) -> #krate::PlutusData {
+                #krate::grow_stack(move || { #to_plutus })
+            }
+
+            fn validate_plutus(data: &#krate::PlutusData) -> Vec<#krate::DecodeError> {
+                #krate::grow_stack(move || { #validate_plutus })
             }
-        })?;
-        return Ok(format);
-    }
-    Ok(None)
-}
 
-fn get_variant(attrs: &[Attribute]) -> Result<Option<u64>, Error> {
-    for a in attrs {
-        let Meta::List(list) = &a.meta else {
-            continue;
-        };
-        if !list.path.is_ident("plutus") {
-            continue;
+            fn check_to_plutus(&self) -> Result<(), #krate::EncodeError> {
+                #krate::grow_stack(move || { #check_plutus })
+            }
         }
-        let mut variant = None;
-        list.parse_nested_meta(|meta| {
-            if meta.path.is_ident("constr") {
-                let value = meta.value()?;
-                let i: LitInt = value.parse()?;
-                variant = Some(i.base10_parse()?);
-                Ok(())
-            } else {
-                Err(Error::new(meta.input.span(), "unrecognized field"))
+
+        // derived types are encoded the same way under both conventions
+        impl #impl_generics #krate::AsPlutusTx for #name #ty_generics #where_clause {
+            fn from_plutus_tx(data: #krate::PlutusData) -> Result<Self, #krate::DecodeError> {
+                <Self as #krate::AsPlutus>::from_plutus(data)
+            }
+
+            fn to_plutus_tx(self) -> #krate::PlutusData {
+                #krate::AsPlutus::to_plutus(self)
+            }
+
+            fn validate_plutus_tx(data: &#krate::PlutusData) -> Vec<#krate::DecodeError> {
+                <Self as #krate::AsPlutus>::validate_plutus(data)
             }
-        })?;
-        return Ok(variant);
+
+            fn check_to_plutus_tx(&self) -> Result<(), #krate::EncodeError> {
+                <Self as #krate::AsPlutus>::check_to_plutus(self)
+            }
+        }
+
+        #fields_impl
+
+        #arbitrary_impl
+    })
+}
+
+/// Wraps a decoding function body with the container's validation hook, if any.
+fn validated(
+    krate: &syn::Path,
+    container: &ContainerAttrs,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match &container.validate {
+        Some(validate) => quote! {
+            let value = (|| -> Result<Self, #krate::DecodeError> { #body })()?;
+            #validate(&value).map_err(#krate::DecodeError::validation)?;
+            Ok(value)
+        },
+        None => body,
     }
-    Ok(None)
 }
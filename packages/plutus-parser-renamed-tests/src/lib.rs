@@ -0,0 +1 @@
+//! Tests for depending on plutus-parser under another name in Cargo.toml.
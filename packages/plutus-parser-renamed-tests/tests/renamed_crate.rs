@@ -0,0 +1,88 @@
+// plutus-parser is only a dependency as `renamed_plutus_parser`, so any generated code
+// which still refers to `plutus_parser` fails to compile.
+
+mod sdk {
+    pub use renamed_plutus_parser as plutus;
+}
+
+use renamed_plutus_parser::testing::assert_encodes_to;
+use renamed_plutus_parser::{BigInt, PlutusData, create_array, create_constr};
+use sdk::plutus::AsPlutus;
+
+#[test]
+fn should_support_renamed_crates() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(crate = "renamed_plutus_parser")]
+    struct Renamed {
+        value: u64,
+    }
+
+    let data = Renamed { value: 3 };
+    let plutus = create_constr(0, vec![PlutusData::BigInt(BigInt::Int(3.into()))]);
+
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_support_reexported_crates() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(crate = "crate::sdk::plutus")]
+    enum Reexported {
+        First(bool),
+        #[plutus(constr = 3)]
+        Second {
+            values: Vec<u8>,
+        },
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(crate = "sdk::plutus", list)]
+    struct Generic<T>(T, Reexported);
+
+    let data = Generic(9u8, Reexported::First(true));
+    let plutus = create_array(vec![
+        PlutusData::BigInt(BigInt::Int(9.into())),
+        create_constr(0, vec![create_constr(1, vec![])]),
+    ]);
+    assert_encodes_to(data, plutus);
+
+    let data = Reexported::Second {
+        values: vec![0xca, 0xfe],
+    };
+    let plutus = create_constr(3, vec![PlutusData::BoundedBytes(vec![0xca, 0xfe].into())]);
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_not_call_inherent_to_plutus_methods() {
+    #[derive(Debug, PartialEq, Eq)]
+    struct Shadowed(u64);
+
+    impl Shadowed {
+        #[allow(clippy::wrong_self_convention)]
+        fn to_plutus(self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    impl AsPlutus for Shadowed {
+        fn from_plutus(data: PlutusData) -> Result<Self, renamed_plutus_parser::DecodeError> {
+            Ok(Self(u64::from_plutus(data)?))
+        }
+
+        fn to_plutus(self) -> PlutusData {
+            AsPlutus::to_plutus(self.0)
+        }
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(crate = "renamed_plutus_parser")]
+    struct Outer {
+        inner: Shadowed,
+    }
+
+    assert_eq!(Shadowed(4).to_plutus(), "4");
+    let data = Outer { inner: Shadowed(4) };
+    let plutus = create_constr(0, vec![PlutusData::BigInt(BigInt::Int(4.into()))]);
+    assert_encodes_to(data, plutus);
+}
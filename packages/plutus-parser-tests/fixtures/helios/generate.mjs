@@ -0,0 +1,50 @@
+// Writes each fixture in this directory from the value of a Helios constant, using the
+// compiler version pinned in package.json. Run `npm install && npm run generate`.
+import { readFileSync, writeFileSync } from "node:fs";
+import { Program } from "@hyperionbt/helios";
+
+const owner = (byte) => `PubKeyHash::new(#${byte.repeat(28)})`;
+const config = `Config{owner: ${owner("11")}, fee: 30}`;
+
+// the values `tests/helios.rs` expects, by fixture name
+const fixtures = {
+  wrapper: ["Wrapper", "Wrapper{amount: 1000000}"],
+  config: ["Config", config],
+  redeemer_swap: ["Redeemer", "Redeemer::Swap{min_out: 5}"],
+  redeemer_cancel: ["Redeemer", "Redeemer::Cancel"],
+  redeemer_update: ["Redeemer", `Redeemer::Update{config: ${config}}`],
+  order: [
+    "Order",
+    `Order{
+      owner: ${owner("22")},
+      deadline: Option[Int]::Some{1700000000000},
+      amounts: Map[ByteArray]Int{#: 2000000, #cafe: 1},
+      wrapper: Wrapper{amount: 1}
+    }`,
+  ],
+  order_without_deadline: [
+    "Order",
+    `Order{
+      owner: ${owner("22")},
+      deadline: Option[Int]::None,
+      amounts: Map[ByteArray]Int{},
+      wrapper: Wrapper{amount: 1}
+    }`,
+  ],
+};
+
+const types = readFileSync(new URL("types.hl", import.meta.url), "utf8").replace(
+  /^module fixtures$/m,
+  "",
+);
+const constants = Object.entries(fixtures)
+  .map(([name, [type, value]]) => `const ${name}: ${type} = ${value}`)
+  .join("\n\n");
+const program = Program.new(
+  `testing fixtures\n${types}\n${constants}\n\nfunc main() -> Bool { true }\n`,
+);
+
+for (const name of Object.keys(fixtures)) {
+  const data = program.evalParam(name).data;
+  writeFileSync(new URL(`${name}.hex`, import.meta.url), `${data.toCborHex()}\n`);
+}
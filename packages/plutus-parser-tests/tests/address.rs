@@ -0,0 +1,200 @@
+use plutus_parser::{
+    Hash,
+    ledger::{Address, AddressError, Credential, Network, Referenced},
+};
+
+// test vectors from CIP-19
+fn key_hash() -> Hash<28> {
+    Hash::new(
+        hex::decode("9493315cd92eb5d8c4304e67b7e16ae36d61d34502694657811a2c8e")
+            .unwrap()
+            .try_into()
+            .unwrap(),
+    )
+}
+
+fn stake_hash() -> Hash<28> {
+    Hash::new(
+        hex::decode("337b62cfff6403a06a3acbc34f8c46003c69fe79a3628cefa9c47251")
+            .unwrap()
+            .try_into()
+            .unwrap(),
+    )
+}
+
+fn script_hash() -> Hash<28> {
+    Hash::new(
+        hex::decode("c37b1b5dc0669f1d3c61a6fddb2e8fde96be87b881c60bce8e8d542f")
+            .unwrap()
+            .try_into()
+            .unwrap(),
+    )
+}
+
+fn pointer() -> Referenced<Credential> {
+    Referenced::Pointer {
+        slot_number: 2498243,
+        transaction_index: 27,
+        certificate_index: 3,
+    }
+}
+
+fn address(payment: Credential, stake: Option<Referenced<Credential>>) -> Address {
+    Address {
+        payment_credential: payment,
+        stake_credential: stake,
+    }
+}
+
+fn assert_bech32(address: Address, network: Network, bech32: &str) {
+    assert_eq!(address.to_bech32(network), bech32);
+    assert_eq!(Address::from_bech32(bech32), Ok((network, address.clone())));
+
+    let bytes = address.to_shelley_bytes(network);
+    assert_eq!(Address::from_shelley_bytes(&bytes), Ok((network, address)));
+}
+
+#[test]
+fn should_convert_every_shelley_address_type() {
+    let key = Credential::VerificationKey(key_hash());
+    let script = Credential::Script(script_hash());
+    let stake_key = Referenced::Inline(Credential::VerificationKey(stake_hash()));
+    let stake_script = Referenced::Inline(Credential::Script(script_hash()));
+
+    let cases = [
+        (
+            address(key.clone(), Some(stake_key.clone())),
+            "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x",
+        ),
+        (
+            address(script.clone(), Some(stake_key)),
+            "addr1z8phkx6acpnf78fuvxn0mkew3l0fd058hzquvz7w36x4gten0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgs9yc0hh",
+        ),
+        (
+            address(key.clone(), Some(stake_script.clone())),
+            "addr1yx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzerkr0vd4msrxnuwnccdxlhdjar77j6lg0wypcc9uar5d2shs2z78ve",
+        ),
+        (
+            address(script.clone(), Some(stake_script)),
+            "addr1x8phkx6acpnf78fuvxn0mkew3l0fd058hzquvz7w36x4gt7r0vd4msrxnuwnccdxlhdjar77j6lg0wypcc9uar5d2shskhj42g",
+        ),
+        (
+            address(key.clone(), Some(pointer())),
+            "addr1gx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer5pnz75xxcrzqf96k",
+        ),
+        (
+            address(script.clone(), Some(pointer())),
+            "addr128phkx6acpnf78fuvxn0mkew3l0fd058hzquvz7w36x4gtupnz75xxcrtw79hu",
+        ),
+        (
+            address(key, None),
+            "addr1vx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzers66hrl8",
+        ),
+        (
+            address(script, None),
+            "addr1w8phkx6acpnf78fuvxn0mkew3l0fd058hzquvz7w36x4gtcyjy7wx",
+        ),
+    ];
+    for (address, bech32) in cases {
+        assert_bech32(address, Network::Mainnet, bech32);
+    }
+}
+
+#[test]
+fn should_convert_testnet_addresses() {
+    assert_bech32(
+        address(
+            Credential::VerificationKey(key_hash()),
+            Some(Referenced::Inline(
+                Credential::VerificationKey(stake_hash()),
+            )),
+        ),
+        Network::Testnet,
+        "addr_test1qz2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgs68faae",
+    );
+}
+
+#[test]
+fn should_encode_pointers_as_variable_length_naturals() {
+    let bytes = address(Credential::VerificationKey(key_hash()), Some(pointer()))
+        .to_shelley_bytes(Network::Mainnet);
+    assert_eq!(
+        hex::encode(bytes),
+        "419493315cd92eb5d8c4304e67b7e16ae36d61d34502694657811a2c8e8198bd431b03"
+    );
+}
+
+#[test]
+fn should_reject_addresses_which_are_not_plutus_data() {
+    assert_eq!(
+        Address::from_bech32("Ae2tdPwUPEZFRbyhz3cpfC2CumGzNkFBN2L42rcUc2yjQpEkxDbkPodpMAi"),
+        Err(AddressError::Byron)
+    );
+    assert_eq!(
+        Address::from_shelley_bytes(&[0x82, 0xd8, 0x18]),
+        Err(AddressError::Byron)
+    );
+    assert_eq!(
+        Address::from_bech32("stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw"),
+        Err(AddressError::Reward)
+    );
+
+    let mut bytes =
+        hex::decode("e1337b62cfff6403a06a3acbc34f8c46003c69fe79a3628cefa9c47251").unwrap();
+    assert_eq!(
+        Address::from_shelley_bytes(&bytes),
+        Err(AddressError::Reward)
+    );
+    bytes[0] = 0x61;
+    assert!(Address::from_shelley_bytes(&bytes).is_ok());
+    bytes[0] = 0x65;
+    assert_eq!(
+        Address::from_shelley_bytes(&bytes),
+        Err(AddressError::UnknownNetwork(5))
+    );
+    bytes[0] = 0x01;
+    assert_eq!(
+        Address::from_shelley_bytes(&bytes),
+        Err(AddressError::WrongLength {
+            expected: 57,
+            actual: 29
+        })
+    );
+    assert_eq!(Address::from_shelley_bytes(&[]), Err(AddressError::Empty));
+}
+
+#[test]
+fn should_reject_mismatched_networks() {
+    let bytes =
+        address(Credential::VerificationKey(key_hash()), None).to_shelley_bytes(Network::Testnet);
+    let hrp = bech32::Hrp::parse("addr").unwrap();
+    let bech32 = bech32::encode::<bech32::Bech32>(hrp, &bytes).unwrap();
+    assert_eq!(
+        Address::from_bech32(&bech32),
+        Err(AddressError::NetworkMismatch {
+            prefix: "addr".to_string(),
+            network: Network::Testnet
+        })
+    );
+
+    assert!(matches!(
+        Address::from_bech32("addr1vx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzers66hrl9"),
+        Err(AddressError::InvalidBech32(_))
+    ));
+}
+
+#[test]
+fn should_reject_bad_pointers() {
+    let mut bytes = address(Credential::VerificationKey(key_hash()), Some(pointer()))
+        .to_shelley_bytes(Network::Mainnet);
+    bytes.pop();
+    assert_eq!(
+        Address::from_shelley_bytes(&bytes),
+        Err(AddressError::InvalidPointer)
+    );
+    bytes.extend([0x03, 0x00]);
+    assert_eq!(
+        Address::from_shelley_bytes(&bytes),
+        Err(AddressError::InvalidPointer)
+    );
+}
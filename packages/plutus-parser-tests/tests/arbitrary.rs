@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+
+use plutus_parser::arbitrary::{DataParams, any_plutus_data, plutus_data};
+use plutus_parser::{AsPlutus, PlutusData, minicbor};
+use proptest::prelude::*;
+
+fn check_range(range: &Range) -> Result<(), String> {
+    if range.min > range.max {
+        return Err(format!("min {} is above max {}", range.min, range.max));
+    }
+    Ok(())
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+#[plutus(arbitrary, validate = "check_range")]
+struct Range {
+    min: u64,
+    max: u64,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+#[plutus(arbitrary)]
+struct Order {
+    #[plutus(strategy = proptest::collection::vec(any::<u8>(), 28))]
+    #[plutus(len = 28)]
+    owner: Vec<u8>,
+    #[plutus(range = 0..=10000)]
+    fee_bps: u64,
+    ranges: Vec<Range>,
+    by_name: BTreeMap<String, Option<i64>>,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+#[plutus(arbitrary)]
+enum Action {
+    Place(Order),
+    Cancel {
+        id: u32,
+    },
+    #[plutus(constr = 9)]
+    Noop,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+#[plutus(arbitrary)]
+enum Tree<T> {
+    Leaf(T),
+    Node(Box<Tree<T>>, Box<Tree<T>>),
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+#[plutus(arbitrary, convention = "plutustx")]
+struct Pair(i8, Vec<u8>);
+
+fn depth(data: &PlutusData) -> u32 {
+    let children: Vec<&PlutusData> = match data {
+        PlutusData::Constr(constr) => constr.fields.iter().collect(),
+        PlutusData::Array(items) => items.iter().collect(),
+        PlutusData::Map(kvps) => kvps.iter().flat_map(|(k, v)| [k, v]).collect(),
+        _ => vec![],
+    };
+    children
+        .into_iter()
+        .map(|child| depth(child) + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+proptest! {
+    #[test]
+    fn should_round_trip_plutus_data(data in any_plutus_data()) {
+        let bytes = minicbor::to_vec(&data).unwrap();
+        prop_assert_eq!(minicbor::decode::<PlutusData>(&bytes).unwrap(), data);
+    }
+
+    #[test]
+    fn should_bound_plutus_data(
+        data in plutus_data(DataParams { max_depth: 2, max_len: 3, max_bytes_len: 70 })
+    ) {
+        prop_assert!(depth(&data) <= 2);
+    }
+
+    #[test]
+    fn should_round_trip_derived_structs(order: Order) {
+        prop_assert_eq!(order.owner.len(), 28);
+        prop_assert!(order.fee_bps <= 10000);
+        prop_assert!(order.ranges.iter().all(|r| r.min <= r.max));
+        prop_assert_eq!(Order::from_plutus(order.clone().to_plutus()), Ok(order));
+    }
+
+    #[test]
+    fn should_round_trip_derived_enums(action: Action) {
+        prop_assert_eq!(Action::from_plutus(action.clone().to_plutus()), Ok(action));
+    }
+
+    #[test]
+    fn should_round_trip_recursive_types(tree: Tree<u8>) {
+        let bytes = tree.clone().to_plutus_bytes();
+        prop_assert_eq!(Tree::from_plutus_bytes(&bytes), Ok(tree));
+    }
+
+    #[test]
+    fn should_round_trip_other_conventions(pair: Pair) {
+        prop_assert_eq!(Pair::from_plutus(pair.clone().to_plutus()), Ok(pair));
+    }
+}
@@ -0,0 +1,5 @@
+#[test]
+fn should_reject_invalid_attributes() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/compile_fail/*.rs");
+}
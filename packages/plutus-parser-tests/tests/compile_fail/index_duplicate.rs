@@ -0,0 +1,11 @@
+use plutus_parser::AsPlutus;
+
+#[derive(AsPlutus)]
+struct Duplicate {
+    #[plutus(index = 0)]
+    id: u64,
+    #[plutus(index = 0)]
+    name: String,
+}
+
+fn main() {}
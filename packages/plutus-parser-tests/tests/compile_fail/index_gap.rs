@@ -0,0 +1,11 @@
+use plutus_parser::AsPlutus;
+
+#[derive(AsPlutus)]
+struct Gap {
+    #[plutus(index = 0)]
+    id: u64,
+    #[plutus(index = 2)]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use plutus_parser::AsPlutus;
+
+#[derive(AsPlutus)]
+struct Partial {
+    #[plutus(index = 0)]
+    id: u64,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use plutus_parser::AsPlutus;
+
+#[derive(AsPlutus)]
+#[plutus(require_index)]
+struct Required {
+    id: u64,
+}
+
+fn main() {}
@@ -0,0 +1,119 @@
+// Checks the helios convention against the fixtures in `fixtures/helios`, which were
+// encoded by hand rather than by the Helios compiler. See the README there.
+
+use std::collections::BTreeMap;
+
+use plutus_parser::{AsPlutus, DecodeError, Hash};
+
+#[derive(AsPlutus, Clone, Debug, PartialEq)]
+#[plutus(convention = "helios")]
+struct Config {
+    owner: Hash<28>,
+    fee: i64,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq)]
+#[plutus(convention = "helios")]
+struct Wrapper {
+    amount: i64,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq)]
+#[plutus(convention = "helios")]
+enum Redeemer {
+    Swap { min_out: i64 },
+    Cancel,
+    Update { config: Config },
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq)]
+#[plutus(convention = "helios")]
+struct Order {
+    owner: Hash<28>,
+    deadline: Option<i64>,
+    amounts: BTreeMap<Vec<u8>, i64>,
+    wrapper: Wrapper,
+}
+
+fn fixture(name: &str) -> Vec<u8> {
+    let path = format!("{}/fixtures/helios/{name}.hex", env!("CARGO_MANIFEST_DIR"));
+    let contents = std::fs::read_to_string(&path).unwrap();
+    hex::decode(contents.trim()).unwrap()
+}
+
+fn assert_fixture<T: AsPlutus + Clone + std::fmt::Debug + PartialEq>(value: T, name: &str) {
+    let bytes = fixture(name);
+    assert_eq!(T::from_plutus_bytes(&bytes).unwrap(), value);
+    assert_eq!(value.to_plutus_bytes(), bytes);
+}
+
+fn config() -> Config {
+    Config {
+        owner: Hash::new([0x11; 28]),
+        fee: 30,
+    }
+}
+
+#[test]
+fn should_encode_single_field_structs_as_the_field() {
+    assert_fixture(Wrapper { amount: 1_000_000 }, "wrapper");
+}
+
+#[test]
+fn should_encode_structs_as_lists() {
+    assert_fixture(config(), "config");
+}
+
+#[test]
+fn should_encode_enums_as_constr() {
+    assert_fixture(Redeemer::Swap { min_out: 5 }, "redeemer_swap");
+    assert_fixture(Redeemer::Cancel, "redeemer_cancel");
+    assert_fixture(Redeemer::Update { config: config() }, "redeemer_update");
+}
+
+#[test]
+fn should_roundtrip_nested_structs() {
+    let order = Order {
+        owner: Hash::new([0x22; 28]),
+        deadline: Some(1_700_000_000_000),
+        amounts: BTreeMap::from([(vec![], 2_000_000), (vec![0xca, 0xfe], 1)]),
+        wrapper: Wrapper { amount: 1 },
+    };
+    assert_fixture(order, "order");
+
+    let order = Order {
+        owner: Hash::new([0x22; 28]),
+        deadline: None,
+        amounts: BTreeMap::new(),
+        wrapper: Wrapper { amount: 1 },
+    };
+    assert_fixture(order, "order_without_deadline");
+}
+
+#[test]
+fn should_support_single_field_tuple_structs() {
+    #[derive(AsPlutus, Clone, Debug, PartialEq)]
+    #[plutus(convention = "helios")]
+    struct Lovelace(i64);
+
+    #[derive(AsPlutus, Clone, Debug, PartialEq)]
+    #[plutus(convention = "helios")]
+    struct Both(Lovelace, Lovelace);
+
+    assert_eq!(hex::encode(Lovelace(5).to_plutus_bytes()), "05");
+    assert_eq!(
+        hex::encode(Both(Lovelace(1), Lovelace(2)).to_plutus_bytes()),
+        "9f0102ff"
+    );
+}
+
+#[test]
+fn should_include_field_names_in_errors() {
+    let bytes = hex::decode("41aa").unwrap();
+    let error = DecodeError::unexpected_type("BigInt", "BoundedBytes").with_field_name("amount");
+    assert_eq!(Wrapper::from_plutus_bytes(&bytes), Err(error));
+
+    let bytes = hex::decode("9f01ff").unwrap();
+    let error = DecodeError::wrong_tuple_field_count(2, 1);
+    assert_eq!(Config::from_plutus_bytes(&bytes), Err(error));
+}
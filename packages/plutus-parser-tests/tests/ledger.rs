@@ -0,0 +1,483 @@
+use plutus_parser::{
+    AsPlutus, Hash, PlutusData,
+    ledger::{
+        Address, Credential, Datum, Input, Interval, IntervalBound, IntervalBoundType, Output,
+        OutputReference, Referenced, ValidityRange, Value,
+    },
+    testing::{assert_encodes_to_hex, assert_roundtrip},
+};
+
+fn key_address() -> Address {
+    Address {
+        payment_credential: Credential::VerificationKey(Hash::new([0x11; 28])),
+        stake_credential: None,
+    }
+}
+
+#[test]
+fn should_encode_credentials() {
+    assert_encodes_to_hex(
+        Credential::VerificationKey(Hash::new([0x11; 28])),
+        "d8799f581c11111111111111111111111111111111111111111111111111111111ff",
+    );
+    assert_encodes_to_hex(
+        Credential::Script(Hash::new([0x22; 28])),
+        "d87a9f581c22222222222222222222222222222222222222222222222222222222ff",
+    );
+}
+
+#[test]
+fn should_encode_addresses() {
+    assert_encodes_to_hex(
+        key_address(),
+        "d8799fd8799f581c11111111111111111111111111111111111111111111111111111111ffd87a80ff",
+    );
+    assert_encodes_to_hex(
+        Address {
+            payment_credential: Credential::VerificationKey(Hash::new([0x11; 28])),
+            stake_credential: Some(Referenced::Inline(Credential::Script(Hash::new(
+                [0x22; 28],
+            )))),
+        },
+        "d8799fd8799f581c11111111111111111111111111111111111111111111111111111111ffd8799fd8799fd87a9f581c22222222222222222222222222222222222222222222222222222222ffffffff",
+    );
+    assert_encodes_to_hex(
+        Address {
+            payment_credential: Credential::Script(Hash::new([0x22; 28])),
+            stake_credential: Some(Referenced::Pointer {
+                slot_number: 1,
+                transaction_index: 2,
+                certificate_index: 3,
+            }),
+        },
+        "d8799fd87a9f581c22222222222222222222222222222222222222222222222222222222ffd8799fd87a9f010203ffffff",
+    );
+}
+
+#[test]
+fn should_encode_output_references() {
+    assert_encodes_to_hex(
+        OutputReference {
+            transaction_id: Hash::new([0xaa; 32]),
+            output_index: 1,
+        },
+        "d8799f5820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa01ff",
+    );
+}
+
+#[test]
+fn should_encode_datums() {
+    assert_encodes_to_hex(Datum::NoDatum, "d87980");
+    assert_encodes_to_hex(
+        Datum::DatumHash(Hash::new([0xbb; 32])),
+        "d87a9f5820bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbff",
+    );
+    assert_encodes_to_hex(
+        Datum::InlineDatum(PlutusData::BoundedBytes(vec![0xca, 0xfe].into())),
+        "d87b9f42cafeff",
+    );
+}
+
+#[test]
+fn should_encode_inputs() {
+    let value = Value::from_lovelace(2_000_000)
+        .with_asset(vec![0x33; 28], b"SWAP".to_vec(), 1)
+        .with_asset(vec![0x33; 28], b"SUNDAE".to_vec(), 5);
+    let input = Input {
+        output_reference: OutputReference {
+            transaction_id: Hash::new([0xaa; 32]),
+            output_index: 0,
+        },
+        output: Output {
+            address: key_address(),
+            value,
+            datum: Datum::NoDatum,
+            reference_script: Some(Hash::new([0x44; 28])),
+        },
+    };
+
+    assert_encodes_to_hex(
+        input,
+        concat!(
+            "d8799f",
+            "d8799f5820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa00ff",
+            "d8799f",
+            "d8799fd8799f581c11111111111111111111111111111111111111111111111111111111ffd87a80ff",
+            "a2",
+            "40a1401a001e8480",
+            "581c33333333333333333333333333333333333333333333333333333333a24653554e44414505445357415001",
+            "d87980",
+            "d8799f581c44444444444444444444444444444444444444444444444444444444ff",
+            "ff",
+            "ff",
+        ),
+    );
+}
+
+#[test]
+fn should_encode_validity_ranges() {
+    let range: ValidityRange = Interval {
+        lower_bound: IntervalBound {
+            bound_type: IntervalBoundType::Finite(1000),
+            is_inclusive: true,
+        },
+        upper_bound: IntervalBound {
+            bound_type: IntervalBoundType::PositiveInfinity,
+            is_inclusive: true,
+        },
+    };
+    assert_encodes_to_hex(
+        range,
+        "d8799fd8799fd87a9f1903e8ffd87a80ffd8799fd87b80d87a80ffff",
+    );
+}
+
+fn map(entries: Vec<(PlutusData, PlutusData)>) -> PlutusData {
+    plutus_parser::create_map(entries)
+}
+
+fn bytes(bytes: &[u8]) -> PlutusData {
+    bytes.to_vec().to_plutus()
+}
+
+#[test]
+fn should_do_value_arithmetic() {
+    let policy = Hash::new([0x33; 28]);
+    let a = Value::from_lovelace(5).with_asset(policy.to_vec(), b"A".to_vec(), 2);
+    let b = Value::from_asset(policy, b"A".to_vec(), 2).with_lovelace(3);
+
+    let sum = (a.clone() + b.clone()).unwrap();
+    assert_eq!(sum.lovelace(), 8);
+    assert_eq!(sum.quantity_of(policy.as_slice(), b"A"), 4);
+
+    let difference = (a.clone() - b.clone()).unwrap();
+    assert_eq!(difference, Value::from_lovelace(2));
+    assert_eq!(
+        difference.policies().collect::<Vec<_>>(),
+        vec![&[] as &[u8]]
+    );
+
+    assert_eq!(-a.clone(), a.clone().checked_negate());
+    assert!((a.clone() - a.clone()).unwrap().is_zero());
+    assert_eq!(
+        a.clone().without_lovelace(),
+        Value::from_asset(policy, b"A".to_vec(), 2)
+    );
+
+    let total = a
+        .checked_sub(Value::from_lovelace(5))
+        .and_then(|total| total.checked_add(policy.to_vec(), b"A".to_vec(), 1))
+        .unwrap();
+    assert_eq!(
+        total.flatten().collect::<Vec<_>>(),
+        vec![(policy.as_slice(), b"A".as_slice(), 3)]
+    );
+}
+
+#[test]
+fn should_encode_value_in_ascending_order() {
+    let value = Value::zero()
+        .with_asset(vec![0x02], b"B".to_vec(), 1)
+        .with_asset(vec![0x02], b"A".to_vec(), 1)
+        .with_asset(vec![0x01], b"A".to_vec(), 0)
+        .with_asset(vec![], vec![], 7);
+    assert_encodes_to_hex(value, "a240a140074102a2414101414201");
+}
+
+#[test]
+fn should_decode_unsorted_value() {
+    let data = map(vec![
+        (
+            bytes(&[0x02]),
+            map(vec![
+                (bytes(b"B"), 1.to_plutus()),
+                (bytes(b"A"), 1.to_plutus()),
+            ]),
+        ),
+        (bytes(&[]), map(vec![(bytes(&[]), 4.to_plutus())])),
+        (bytes(&[]), map(vec![(bytes(&[]), 3.to_plutus())])),
+        (bytes(&[0x01]), map(vec![(bytes(b"A"), 0.to_plutus())])),
+    ]);
+    let expected = Value::from_lovelace(7)
+        .with_asset(vec![0x02], b"A".to_vec(), 1)
+        .with_asset(vec![0x02], b"B".to_vec(), 1);
+    assert_eq!(Value::from_plutus(data.clone()), Ok(expected));
+
+    let error = Value::from_plutus_strict(data).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "decode error at [(key #1)]: validation failed: keys are not in ascending order"
+    );
+}
+
+#[test]
+fn should_reject_duplicate_value_keys_when_strict() {
+    let data = map(vec![(
+        bytes(&[0x02]),
+        map(vec![
+            (bytes(b"A"), 1.to_plutus()),
+            (bytes(b"A"), 2.to_plutus()),
+        ]),
+    )]);
+    assert_eq!(
+        Value::from_plutus(data.clone()),
+        Ok(Value::zero().with_asset(vec![0x02], b"A".to_vec(), 3))
+    );
+    let error = Value::from_plutus_strict(data).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "decode error at [(value #0)][(key #1)]: duplicate key in map entry #1"
+    );
+}
+
+#[test]
+fn should_hold_quantities_beyond_an_i64() {
+    let policy = Hash::new([0x02; 28]);
+    let value = Value::from_asset(policy, b"A".to_vec(), i64::MAX.into())
+        .checked_add(policy.to_vec(), b"A".to_vec(), 1)
+        .unwrap();
+    assert_eq!(
+        value.quantity_of(policy.as_slice(), b"A"),
+        i128::from(i64::MAX) + 1
+    );
+    assert_roundtrip(&value);
+
+    let data = map(vec![(
+        bytes(&[0x02]),
+        map(vec![
+            (bytes(b""), u64::MAX.to_plutus()),
+            (bytes(b""), u64::MAX.to_plutus()),
+        ]),
+    )]);
+    let value = Value::from_plutus(data).unwrap();
+    assert_eq!(value.quantity_of(&[0x02], b""), 2 * i128::from(u64::MAX));
+    assert_encodes_to_hex(value, "a14102a140c24901fffffffffffffffe");
+    assert_roundtrip(&Value::from_lovelace(i128::MIN).with_asset(vec![0x02], vec![], i128::MAX));
+}
+
+#[test]
+fn should_reject_overflowing_value_quantities() {
+    let data = map(vec![(
+        bytes(&[0x02]),
+        map(vec![
+            (bytes(b""), i128::MAX.to_plutus()),
+            (bytes(b""), 1.to_plutus()),
+        ]),
+    )]);
+    let error = Value::from_plutus(data).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "decode error at [(value #0)][(value #1)]: value {} + 1 out of range",
+            i128::MAX
+        )
+    );
+
+    let minimum = Value::from_asset(Hash::from([2; 28]), b"A".to_vec(), i128::MIN);
+    assert_eq!(minimum.clone().checked_negate(), None);
+    assert_eq!(
+        minimum.clone().checked_add(vec![2; 28], b"A".to_vec(), -1),
+        None
+    );
+    assert_eq!(minimum.clone().checked_merge(minimum.clone()), None);
+    assert_eq!(Value::zero() - minimum.clone(), None);
+    assert_eq!(-minimum, None);
+
+    let value = Value::from_lovelace(i128::MIN).with_lovelace(i128::MAX);
+    assert_eq!(value.lovelace(), i128::MAX);
+    assert_eq!(value.with_lovelace(0), Value::zero());
+}
+
+#[test]
+fn should_reject_zero_quantities_when_strict() {
+    let data = map(vec![(
+        bytes(&[0x02]),
+        map(vec![
+            (bytes(b"A"), 0.to_plutus()),
+            (bytes(b"B"), 1.to_plutus()),
+        ]),
+    )]);
+    assert_eq!(
+        Value::from_plutus(data.clone()),
+        Ok(Value::zero().with_asset(vec![0x02], b"B".to_vec(), 1))
+    );
+    let error = Value::from_plutus_strict(data).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "decode error at [(value #0)][(value #0)]: validation failed: quantity is zero"
+    );
+    assert_eq!(error.positions(), [1, 1]);
+
+    let data = map(vec![(bytes(&[0x02]), map(vec![]))]);
+    assert_eq!(Value::from_plutus(data.clone()), Ok(Value::zero()));
+    let error = Value::from_plutus_strict(data).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "decode error at [(value #0)]: validation failed: policy has no assets"
+    );
+}
+
+#[test]
+fn should_check_interval_membership() {
+    let closed = Interval::between(1, 5);
+    assert!(closed.contains(&1) && closed.contains(&5));
+    assert!(!closed.contains(&0) && !closed.contains(&6));
+
+    let open = Interval::entirely_between(1, 5);
+    assert!(!open.contains(&1) && open.contains(&2) && !open.contains(&5));
+
+    assert!(Interval::after(3).contains(&i64::MAX));
+    assert!(!Interval::entirely_after(3).contains(&3));
+    assert!(Interval::before(3).contains(&i64::MIN));
+    assert!(!Interval::entirely_before(3).contains(&3));
+    assert!(Interval::everything().contains(&0));
+    assert!(!Interval::<i64>::empty().contains(&0));
+}
+
+#[test]
+fn should_check_interval_emptiness() {
+    assert!(Interval::<i64>::empty().is_empty());
+    assert!(!Interval::<i64>::everything().is_empty());
+    assert!(Interval::between(5, 1).is_empty());
+    assert!(!Interval::between(3, 3).is_empty());
+    assert!(Interval::entirely_between(3, 3).is_empty());
+}
+
+#[test]
+fn should_treat_adjacent_open_integer_bounds_as_empty() {
+    // like Aiken, (1, 2) holds no integers
+    assert!(Interval::entirely_between(1, 2).is_empty());
+    assert!(!Interval::entirely_between(1, 3).is_empty());
+    assert!(Interval::entirely_between(i64::MAX - 1, i64::MAX).is_empty());
+    assert!(Interval::entirely_between(1u64, 2).is_empty());
+
+    // only open intervals are shifted, so [1, 2) and (1, 2] still hold a value
+    let half_open = Interval {
+        lower_bound: Interval::between(1, 2).lower_bound,
+        upper_bound: Interval::entirely_between(1, 2).upper_bound,
+    };
+    assert!(!half_open.is_empty());
+    let half_open = Interval {
+        lower_bound: Interval::entirely_between(1, 2).lower_bound,
+        upper_bound: Interval::between(1, 2).upper_bound,
+    };
+    assert!(!half_open.is_empty());
+
+    assert!(
+        Interval::entirely_after(1)
+            .intersection(Interval::entirely_before(2))
+            .is_empty()
+    );
+
+    // times have no adjacent values
+    let start = std::time::UNIX_EPOCH;
+    let end = start + std::time::Duration::from_nanos(1);
+    assert!(!Interval::entirely_between(start, end).is_empty());
+}
+
+#[test]
+fn should_compare_adjacent_integer_bounds_as_equal() {
+    // (1 and [2 are the same lower bound, so either may be kept
+    let open = Interval::entirely_after(1);
+    let closed = Interval::after(2);
+    assert_eq!(open.clone().intersection(closed.clone()), open);
+    assert_eq!(closed.clone().intersection(open.clone()), closed);
+    assert_eq!(open.clone().hull(closed.clone()), open);
+
+    let open = Interval::entirely_before(5);
+    let closed = Interval::before(4);
+    assert_eq!(open.clone().hull(closed.clone()), open);
+    assert_eq!(closed.clone().hull(open.clone()), closed);
+}
+
+#[test]
+fn should_compare_intervals_with_points() {
+    assert!(Interval::after(5).is_entirely_after(&4));
+    assert!(!Interval::after(5).is_entirely_after(&5));
+    assert!(Interval::entirely_after(5).is_entirely_after(&5));
+    assert!(!Interval::<i64>::everything().is_entirely_after(&0));
+
+    assert!(Interval::before(5).is_entirely_before(&6));
+    assert!(!Interval::before(5).is_entirely_before(&5));
+    assert!(Interval::entirely_before(5).is_entirely_before(&5));
+    assert!(!Interval::<i64>::everything().is_entirely_before(&0));
+}
+
+#[test]
+fn should_combine_intervals() {
+    let a = Interval::between(0, 10);
+    let b = Interval::entirely_between(5, 15);
+
+    let intersection = a.clone().intersection(b.clone());
+    assert_eq!(
+        intersection,
+        Interval {
+            lower_bound: b.lower_bound.clone(),
+            upper_bound: a.upper_bound.clone(),
+        }
+    );
+    assert!(!intersection.contains(&5) && intersection.contains(&10));
+
+    let hull = a.clone().hull(b.clone());
+    assert_eq!(
+        hull,
+        Interval {
+            lower_bound: a.lower_bound.clone(),
+            upper_bound: b.upper_bound.clone(),
+        }
+    );
+
+    // at the same point, the intersection keeps the exclusive bound and the hull the inclusive one
+    let c = Interval::entirely_between(0, 10);
+    assert_eq!(a.clone().intersection(c.clone()), c);
+    assert_eq!(a.clone().hull(c), a);
+
+    assert!(
+        Interval::before(1)
+            .intersection(Interval::after(2))
+            .is_empty()
+    );
+}
+
+#[test]
+fn should_encode_times_as_posix_millis() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use plutus_parser::ledger::{from_posix_millis, to_posix_millis};
+
+    let start = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+    assert_eq!(
+        to_posix_millis(start + Duration::from_micros(999)),
+        Ok(1_700_000_000_123)
+    );
+    assert_eq!(
+        from_posix_millis(-5),
+        Ok(UNIX_EPOCH - Duration::from_millis(5))
+    );
+    assert_eq!(to_posix_millis(from_posix_millis(-5).unwrap()), Ok(-5));
+
+    let times = Interval::between(start, start + Duration::from_secs(60));
+    let millis = Interval::try_from(times.clone()).unwrap();
+    assert_eq!(
+        millis,
+        Interval::between(1_700_000_000_123, 1_700_000_060_123)
+    );
+    assert_eq!(times.clone().to_plutus(), millis.clone().to_plutus());
+    assert_eq!(Interval::try_from(millis), Ok(times));
+}
+
+#[test]
+fn should_reject_times_outside_posix_millis() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use plutus_parser::{TryToPlutus, ledger::to_posix_millis};
+
+    let last = UNIX_EPOCH + Duration::from_millis(i64::MAX as u64);
+    assert_eq!(to_posix_millis(last), Ok(i64::MAX));
+
+    let late = last + Duration::from_millis(1);
+    assert!(to_posix_millis(late).is_err());
+    assert!(Interval::<i64>::try_from(Interval::after(late)).is_err());
+    assert!(late.check_to_plutus().is_err());
+    assert!(Interval::between(last, late).try_to_plutus().is_err());
+}
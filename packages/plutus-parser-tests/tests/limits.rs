@@ -0,0 +1,157 @@
+use plutus_parser::{
+    AsPlutus, DecodeError, DecodeErrorKind, DecodeLimits, Limit, PlutusData, Span,
+};
+
+#[derive(AsPlutus, Debug, PartialEq, Eq)]
+struct Order {
+    owner: Vec<u8>,
+    amounts: Vec<u64>,
+}
+
+fn limits(max_depth: usize, max_elements: usize, max_bytes_len: usize) -> DecodeLimits {
+    DecodeLimits {
+        max_depth,
+        max_elements,
+        max_bytes_len,
+        ..DecodeLimits::default()
+    }
+}
+
+fn decode<T: AsPlutus>(hex: &str, limits: DecodeLimits) -> Result<T, DecodeError> {
+    T::from_plutus_bytes_with_limits(&hex::decode(hex).unwrap(), limits)
+}
+
+fn exceeded(hex: &str, limits: DecodeLimits) -> (Limit, Option<Span>) {
+    let error = decode::<PlutusData>(hex, limits).unwrap_err();
+    match error.kind() {
+        DecodeErrorKind::LimitExceeded { limit, .. } => (*limit, error.span()),
+        kind => panic!("unexpected error {kind}"),
+    }
+}
+
+fn at(offset: usize) -> Option<Span> {
+    Some(Span { offset, len: 1 })
+}
+
+#[test]
+fn should_decode_within_limits() {
+    let order = decode::<Order>("d8799f41aa9f0102ffff", DecodeLimits::default()).unwrap();
+    assert_eq!(
+        order,
+        Order {
+            owner: vec![0xaa],
+            amounts: vec![1, 2],
+        }
+    );
+    assert!(decode::<PlutusData>("8180", limits(2, 1, 0)).is_ok());
+    assert!(decode::<PlutusData>("a201020304", limits(1, 2, 0)).is_ok());
+}
+
+#[test]
+fn should_limit_depth() {
+    assert_eq!(exceeded("818180", limits(2, 10, 10)), (Limit::Depth, at(2)));
+    // tag 102 wraps the index and fields of a general constructor in a list, which
+    // counts as one level like the fields of any other constructor
+    assert_eq!(
+        exceeded("d86682078180", limits(1, 10, 10)),
+        (Limit::Depth, at(5))
+    );
+    assert!(decode::<PlutusData>("d86682078180", limits(2, 10, 10)).is_ok());
+
+    let data: PlutusData =
+        plutus_parser::minicbor::decode(&hex::decode("d86682078180").unwrap()).unwrap();
+    assert_eq!(
+        PlutusData::from_plutus_with_limits(data.clone(), limits(1, 10, 10)),
+        Err(DecodeError::limit_exceeded(Limit::Depth, 1))
+    );
+    assert!(PlutusData::from_plutus_with_limits(data, limits(2, 10, 10)).is_ok());
+}
+
+#[test]
+fn should_limit_elements() {
+    assert_eq!(
+        exceeded("83010203", limits(10, 2, 10)),
+        (Limit::Elements, at(0))
+    );
+    assert_eq!(
+        exceeded("9f010203ff", limits(10, 2, 10)),
+        (Limit::Elements, at(3))
+    );
+    assert_eq!(
+        exceeded("a3010203040506", limits(10, 2, 10)),
+        (Limit::Elements, at(0))
+    );
+    assert_eq!(
+        exceeded("bf010203040506ff", limits(10, 2, 10)),
+        (Limit::Elements, at(5))
+    );
+    // without a limit on elements, there is no limit on map entries either
+    let unlimited = limits(10, usize::MAX, 10);
+    assert!(decode::<PlutusData>("a3010203040506", unlimited).is_ok());
+}
+
+#[test]
+fn should_limit_bytes_len() {
+    assert_eq!(
+        exceeded("43aabbcc", limits(10, 10, 2)),
+        (Limit::BytesLen, at(0))
+    );
+    assert_eq!(
+        exceeded("5f41aa42bbccff", limits(10, 10, 2)),
+        (Limit::BytesLen, at(0))
+    );
+    // a bignum
+    assert_eq!(
+        exceeded("81c243010000", limits(10, 10, 2)),
+        (Limit::BytesLen, at(1))
+    );
+}
+
+#[test]
+fn should_limit_size() {
+    let limits = DecodeLimits {
+        max_size: 3,
+        ..DecodeLimits::default()
+    };
+    let error = decode::<PlutusData>("83010203", limits).unwrap_err();
+    assert_eq!(error, DecodeError::limit_exceeded(Limit::Size, 3));
+    assert_eq!(
+        error.to_string(),
+        "decode error at : input size exceeds the limit of 3"
+    );
+}
+
+#[test]
+fn should_report_invalid_cbor_while_checking() {
+    let error = decode::<PlutusData>("8301", DecodeLimits::default()).unwrap_err();
+    assert!(matches!(error.kind(), DecodeErrorKind::InvalidCbor(_)));
+}
+
+#[test]
+fn should_limit_decoded_data() {
+    let data: PlutusData =
+        plutus_parser::minicbor::decode(&hex::decode("d8799f41aa9f010203ffff").unwrap()).unwrap();
+    assert_eq!(
+        Order::from_plutus_with_limits(data.clone(), limits(10, 2, 10)),
+        Err(DecodeError::limit_exceeded(Limit::Elements, 2))
+    );
+    assert_eq!(
+        Order::from_plutus_with_limits(data.clone(), limits(1, 10, 10)),
+        Err(DecodeError::limit_exceeded(Limit::Depth, 1))
+    );
+    assert_eq!(
+        Order::from_plutus_with_limits(data.clone(), limits(10, 10, 0)),
+        Err(DecodeError::limit_exceeded(Limit::BytesLen, 0))
+    );
+    assert!(Order::from_plutus_with_limits(data, limits(2, 3, 1)).is_ok());
+}
+
+#[test]
+fn should_reject_deep_input_without_recursing() {
+    let mut bytes = vec![0x81; 100_000];
+    bytes.push(0x80);
+    let error =
+        PlutusData::from_plutus_bytes_with_limits(&bytes, DecodeLimits::default()).unwrap_err();
+    assert_eq!(error, DecodeError::limit_exceeded(Limit::Depth, 128));
+    assert_eq!(error.span(), at(128));
+}
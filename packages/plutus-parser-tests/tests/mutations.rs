@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use plutus_parser::testing::{MutationKind, Outcome, assert_mutations, check_mutations, mutations};
+use plutus_parser::{AsPlutus, DecodeError, PlutusData, create_array, parse_array};
+
+fn check_range(range: &Range) -> Result<(), String> {
+    if range.min > range.max {
+        return Err(format!("min {} is above max {}", range.min, range.max));
+    }
+    Ok(())
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+#[plutus(validate = "check_range")]
+struct Range {
+    min: u64,
+    max: u64,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+struct Order {
+    #[plutus(len = 2)]
+    owner: Vec<u8>,
+    #[plutus(range = 0..=10000)]
+    fee_bps: u64,
+    ranges: Vec<Range>,
+    by_name: BTreeMap<String, Option<i64>>,
+    flag: bool,
+    pair: (u8, [u8; 2]),
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+enum Action {
+    Place(Order),
+    Cancel { id: u32 },
+}
+
+fn order() -> Order {
+    Order {
+        owner: vec![1, 2],
+        fee_bps: 5,
+        ranges: vec![Range { min: 1, max: 2 }],
+        by_name: BTreeMap::from([("a".into(), Some(-1))]),
+        flag: true,
+        pair: (1, [3, 4]),
+    }
+}
+
+// decodes the first item of a list, ignoring the rest
+#[derive(Debug)]
+struct First(u64);
+
+impl AsPlutus for First {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        let items = parse_array(data)?;
+        let first = items
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| create_array(vec![]));
+        u64::from_plutus(first).map(First)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        create_array(vec![self.0.to_plutus()])
+    }
+}
+
+#[test]
+fn should_mutate_every_value() {
+    let data = Range { min: 1, max: 2 }.to_plutus();
+    let found: Vec<(MutationKind, Vec<usize>)> = mutations(&data)
+        .into_iter()
+        .map(|mutation| (mutation.kind, mutation.positions))
+        .collect();
+    let wrong_type = |from, to| MutationKind::WrongType { from, to };
+    assert_eq!(
+        found,
+        vec![
+            (MutationKind::WrongVariant { from: 0, to: 1 }, vec![]),
+            (MutationKind::MissingField, vec![]),
+            (MutationKind::ExtraField, vec![]),
+            (wrong_type("Constr", "BigInt"), vec![]),
+            (MutationKind::OutOfRange, vec![0]),
+            (wrong_type("BigInt", "BoundedBytes"), vec![0]),
+            (MutationKind::OutOfRange, vec![1]),
+            (wrong_type("BigInt", "BoundedBytes"), vec![1]),
+        ]
+    );
+}
+
+#[test]
+fn should_change_only_the_mutated_value() {
+    let data = Range { min: 1, max: 2 }.to_plutus();
+    let mutation = mutations(&data)
+        .into_iter()
+        .find(|mutation| mutation.positions == [1] && mutation.kind != MutationKind::OutOfRange)
+        .unwrap();
+    assert_eq!(
+        hex::encode(plutus_parser::minicbor::to_vec(&mutation.data).unwrap()),
+        "d8799f0140ff"
+    );
+}
+
+#[test]
+fn should_handle_mutations_of_derived_types() {
+    assert_mutations(Action::Place(order()));
+    assert_mutations(Action::Cancel { id: 7 });
+}
+
+#[test]
+fn should_report_where_mutations_were_rejected() {
+    let results = check_mutations(order());
+    let rejected = |kind: MutationKind, positions: &[usize]| {
+        let result = results
+            .iter()
+            .find(|r| r.mutation.kind == kind && r.mutation.positions == positions)
+            .unwrap();
+        match &result.outcome {
+            Outcome::Rejected(error) => error,
+            outcome => panic!("{} was not rejected: {outcome:?}", result.mutation),
+        }
+    };
+
+    assert_eq!(
+        rejected(MutationKind::OutOfRange, &[2, 0, 1]),
+        &DecodeError::out_of_range("0x0100000000000000000000000000000000")
+            .with_field_name("max")
+            .with_index(0)
+            .with_field_name("ranges")
+    );
+    assert_eq!(
+        rejected(MutationKind::WrongLength { from: 2, to: 3 }, &[0]),
+        &DecodeError::validation("expected length 2, found 3").with_field_name("owner")
+    );
+    assert_eq!(
+        rejected(MutationKind::WrongVariant { from: 1, to: 2 }, &[4]),
+        &DecodeError::unexpected_variant(2).with_field_name("flag")
+    );
+
+    // any list of ranges is valid
+    let accepted = results
+        .iter()
+        .filter(|r| r.outcome == Outcome::Accepted)
+        .map(|r| (&r.mutation.kind, r.mutation.positions.as_slice()));
+    assert!(accepted.eq([
+        (&MutationKind::MissingField, [2].as_slice()),
+        (&MutationKind::ExtraField, &[2]),
+        (&MutationKind::WrongLength { from: 1, to: 2 }, &[3, 0]),
+        (&MutationKind::WrongLength { from: 1, to: 0 }, &[3, 0]),
+    ]));
+}
+
+#[test]
+fn should_catch_types_which_ignore_data() {
+    let results = check_mutations(First(1));
+    let lossy: Vec<String> = results
+        .iter()
+        .filter(|r| r.outcome == Outcome::Lossy)
+        .map(|r| r.to_string())
+        .collect();
+    assert_eq!(
+        lossy,
+        vec!["extra field added at []: decoded, but lost data"]
+    );
+
+    // the error for the missing item isn't located at the list
+    let misreported = results
+        .iter()
+        .find(|r| matches!(r.outcome, Outcome::Misreported(_)))
+        .unwrap();
+    assert_eq!(misreported.mutation.kind, MutationKind::MissingField);
+}
+
+#[test]
+#[should_panic(expected = "extra field added at []: decoded, but lost data")]
+fn should_fail_assertions_for_mishandled_mutations() {
+    assert_mutations(First(1));
+}
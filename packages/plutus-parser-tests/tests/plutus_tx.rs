@@ -0,0 +1,235 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::num::{NonZeroI64, NonZeroU8};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use either::Either;
+use plutus_parser::{
+    AsPlutus, AsPlutusTx, BigInt, BoundedBytes, Dict, Hash, Pairs, PlutusData, PlutusTx,
+    testing::assert_encodes_to_hex,
+};
+use smallvec::SmallVec;
+
+// the value has the same encoding under both conventions
+fn assert_both<T: AsPlutus + AsPlutusTx + Clone + std::fmt::Debug + PartialEq>(
+    data: T,
+    cbor: &str,
+) {
+    assert_encodes_to_hex(data.clone(), cbor);
+    assert_encodes_to_hex(PlutusTx(data), cbor);
+}
+
+#[test]
+fn should_encode_primitives_like_aiken() {
+    assert_both(true, "d87a80");
+    assert_both(false, "d87980");
+    assert_both(7u8, "07");
+    assert_both(1337u16, "190539");
+    assert_both(1337u32, "190539");
+    assert_both(1337u64, "190539");
+    assert_both(-1i8, "20");
+    assert_both(-1i16, "20");
+    assert_both(-1i32, "20");
+    assert_both(-1i64, "20");
+    assert_both(NonZeroU8::new(7).unwrap(), "07");
+    assert_both(NonZeroI64::new(-1).unwrap(), "20");
+    assert_both(BigInt::Int(9001.into()), "192329");
+    assert_both(BoundedBytes::from(vec![0xca, 0xfe]), "42cafe");
+    assert_both("hi".to_string(), "426869");
+    assert_both(vec![0xcau8, 0xfe], "42cafe");
+    assert_both([0xcau8, 0xfe], "42cafe");
+    assert_both(Hash::<2>::new([0xca, 0xfe]), "42cafe");
+    assert_both(bytes::Bytes::from_static(&[0xca, 0xfe]), "42cafe");
+    assert_both((), "d87980");
+    assert_both(PlutusData::BigInt(BigInt::Int(1.into())), "01");
+}
+
+#[test]
+fn should_encode_containers_like_aiken() {
+    assert_both(Some(1i64), "d8799f01ff");
+    assert_both(None::<i64>, "d87a80");
+    assert_both(vec![1i64, 2], "9f0102ff");
+    assert_both(Vec::<i64>::new(), "80");
+    assert_both([1i64, 2], "9f0102ff");
+    assert_both(VecDeque::from([1i64, 2]), "9f0102ff");
+    assert_both(Box::new(1i64), "01");
+    assert_both(Rc::new(1i64), "01");
+    assert_both(Arc::new(1i64), "01");
+    assert_both(std::borrow::Cow::<str>::Owned("hi".into()), "426869");
+    assert_both(BTreeSet::from([2i64, 1]), "9f0102ff");
+    assert_both(HashSet::from([2i64, 1]), "9f0102ff");
+    assert_both(BTreeMap::from([(2i64, 3i64), (1, 2)]), "a201020203");
+    assert_both(HashMap::from([(2i64, 3i64), (1, 2)]), "a201020203");
+    assert_both(Dict::from([(2i64, 3i64), (1, 2)]), "a201020203");
+    assert_both(Pairs::from(vec![(2i64, 3i64), (2, 2)]), "a202030202");
+    assert_both(SmallVec::<[i64; 2]>::from_vec(vec![1, 2]), "9f0102ff");
+    assert_both(Either::<i64, bool>::Left(1), "d8799f01ff");
+    assert_both(Either::<i64, bool>::Right(true), "d87a9fd87a80ff");
+}
+
+#[test]
+fn should_encode_tuples_as_constr() {
+    assert_encodes_to_hex((1i64, 2i64), "9f0102ff");
+    assert_encodes_to_hex(PlutusTx((1i64, 2i64)), "d8799f0102ff");
+    assert_encodes_to_hex(PlutusTx((1i64, 2i64, 3i64)), "d8799f010203ff");
+    assert_encodes_to_hex(
+        PlutusTx((
+            1i64, 2i64, 3i64, 4i64, 5i64, 6i64, 7i64, 8i64, 9i64, 10i64, 11i64, 12i64,
+        )),
+        "d8799f0102030405060708090a0b0cff",
+    );
+    assert_encodes_to_hex(PlutusTx(((1i64, 2i64), 3i64)), "d8799fd8799f0102ff03ff");
+}
+
+#[test]
+fn should_encode_nested_tuples_as_constr() {
+    assert_encodes_to_hex(PlutusTx(vec![(1i64, 2i64)]), "9fd8799f0102ffff");
+    assert_encodes_to_hex(PlutusTx(Some((1i64, 2i64))), "d8799fd8799f0102ffff");
+    assert_encodes_to_hex(
+        PlutusTx(BTreeMap::from([(1i64, (2i64, 3i64))])),
+        "a101d8799f0203ff",
+    );
+    assert_encodes_to_hex(
+        PlutusTx(Pairs::from(vec![((1i64, 2i64), 3i64)])),
+        "a1d8799f0102ff03",
+    );
+    assert_encodes_to_hex(PlutusTx([(1i64, 2i64)]), "9fd8799f0102ffff");
+}
+
+#[test]
+fn should_reject_tuples_with_the_wrong_encoding() {
+    let aiken = hex::decode("9f0102ff").unwrap();
+    assert!(PlutusTx::<(i64, i64)>::from_plutus_bytes(&aiken).is_err());
+
+    let wrong_variant = hex::decode("d87a9f0102ff").unwrap();
+    assert!(PlutusTx::<(i64, i64)>::from_plutus_bytes(&wrong_variant).is_err());
+
+    let wrong_length = hex::decode("d8799f010203ff").unwrap();
+    assert!(PlutusTx::<(i64, i64)>::from_plutus_bytes(&wrong_length).is_err());
+}
+
+#[test]
+fn should_support_container_convention() {
+    #[derive(AsPlutus, Clone, Debug, PartialEq)]
+    #[plutus(convention = "plutustx")]
+    struct Pool {
+        assets: (Vec<u8>, Vec<u8>),
+        fees: Vec<(i64, i64)>,
+        owner: Option<Vec<u8>>,
+    }
+
+    let pool = Pool {
+        assets: (vec![0xaa], vec![0xbb]),
+        fees: vec![(1, 2)],
+        owner: None,
+    };
+    assert_encodes_to_hex(pool, "d8799fd8799f41aa41bbff9fd8799f0102ffffd87a80ff");
+}
+
+#[test]
+fn should_support_enum_convention() {
+    #[derive(AsPlutus, Clone, Debug, PartialEq)]
+    #[plutus(convention = "plutustx")]
+    enum Action {
+        Swap((i64, i64)),
+        Withdraw { amounts: (i64, i64) },
+    }
+
+    assert_encodes_to_hex(Action::Swap((1, 2)), "d8799fd8799f0102ffff");
+    assert_encodes_to_hex(Action::Withdraw { amounts: (1, 2) }, "d87a9fd8799f0102ffff");
+}
+
+#[test]
+fn should_support_field_convention() {
+    #[derive(AsPlutus, Clone, Debug, PartialEq)]
+    struct Mixed {
+        aiken: (i64, i64),
+        #[plutus(convention = "plutustx")]
+        plutus_tx: (i64, i64),
+    }
+
+    #[derive(AsPlutus, Clone, Debug, PartialEq)]
+    #[plutus(convention = "plutustx")]
+    struct Overridden {
+        plutus_tx: (i64, i64),
+        #[plutus(convention = "aiken")]
+        aiken: (i64, i64),
+    }
+
+    assert_encodes_to_hex(
+        Mixed {
+            aiken: (1, 2),
+            plutus_tx: (3, 4),
+        },
+        "d8799f9f0102ffd8799f0304ffff",
+    );
+    assert_encodes_to_hex(
+        Overridden {
+            plutus_tx: (1, 2),
+            aiken: (3, 4),
+        },
+        "d8799fd8799f0102ff9f0304ffff",
+    );
+}
+
+#[test]
+fn should_support_pairs_with_convention() {
+    #[derive(AsPlutus, Clone, Debug, PartialEq)]
+    #[plutus(convention = "plutustx")]
+    struct Lookup {
+        #[plutus(pairs)]
+        entries: Vec<(i64, (i64, i64))>,
+    }
+
+    assert_encodes_to_hex(
+        Lookup {
+            entries: vec![(1, (2, 3))],
+        },
+        "d8799fa101d8799f0203ffff",
+    );
+}
+
+#[test]
+fn should_infer_bounds_for_convention() {
+    #[derive(AsPlutus, Clone, Debug, PartialEq)]
+    #[plutus(convention = "plutustx")]
+    struct Wrapper<T> {
+        value: T,
+    }
+
+    #[derive(AsPlutus, Clone, Debug, PartialEq)]
+    struct Mixed<A, B> {
+        aiken: A,
+        #[plutus(convention = "plutustx")]
+        plutus_tx: B,
+    }
+
+    assert_encodes_to_hex(
+        Wrapper {
+            value: (1i64, 2i64),
+        },
+        "d8799fd8799f0102ffff",
+    );
+    assert_encodes_to_hex(
+        Mixed {
+            aiken: (1i64, 2i64),
+            plutus_tx: (3i64, 4i64),
+        },
+        "d8799f9f0102ffd8799f0304ffff",
+    );
+}
+
+#[test]
+fn should_encode_derived_types_the_same_under_both_conventions() {
+    #[derive(AsPlutus, Clone, Debug, PartialEq)]
+    struct Point {
+        x: i64,
+        y: i64,
+    }
+
+    assert_both(Point { x: 1, y: 2 }, "d8799f0102ff");
+    assert_encodes_to_hex(
+        PlutusTx((Point { x: 1, y: 2 }, 3i64)),
+        "d8799fd8799f0102ff03ff",
+    );
+}
@@ -0,0 +1,138 @@
+use plutus_parser::{
+    AsPlutus, DecodeError, DecodeErrorKind, DecodeLimits, Limit, PlutusData, TryToPlutus,
+    create_array,
+};
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+enum List {
+    Cons(u64, Box<List>),
+    Nil,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+enum Tree<T> {
+    Leaf(T),
+    Node {
+        left: Box<Tree<T>>,
+        right: Box<Tree<T>>,
+    },
+}
+
+fn list(values: &[u64]) -> List {
+    values
+        .iter()
+        .rev()
+        .fold(List::Nil, |tail, value| List::Cons(*value, Box::new(tail)))
+}
+
+// the encoding of a list of `depth` ones
+fn list_hex(depth: usize) -> String {
+    format!("{}d87a80{}", "d8799f01".repeat(depth), "ff".repeat(depth))
+}
+
+#[test]
+fn should_round_trip_recursive_types() {
+    let value = list(&[1, 2]);
+    let bytes = value.clone().to_plutus_bytes();
+    assert_eq!(hex::encode(&bytes), "d8799f01d8799f02d87a80ffff");
+    assert_eq!(List::from_plutus_bytes(&bytes), Ok(value));
+
+    let tree: Tree<Vec<u8>> = Tree::Node {
+        left: Box::new(Tree::Leaf(vec![0xaa])),
+        right: Box::new(Tree::Node {
+            left: Box::new(Tree::Leaf(vec![])),
+            right: Box::new(Tree::Leaf(vec![0xbb])),
+        }),
+    };
+    let bytes = tree.clone().to_plutus_bytes();
+    assert_eq!(
+        hex::encode(&bytes),
+        "d87a9fd8799f41aaffd87a9fd8799f40ffd8799f41bbffffff"
+    );
+    assert_eq!(Tree::from_plutus_bytes(&bytes), Ok(tree));
+}
+
+#[test]
+fn should_report_paths_through_recursive_types() {
+    // the second value is -1
+    let bytes = hex::decode("d8799f01d8799f20d87a80ffff").unwrap();
+    assert_eq!(
+        List::from_plutus_bytes(&bytes),
+        Err(DecodeError::out_of_range(-1)
+            .with_field_name(0)
+            .with_variant("Cons")
+            .with_field_name(1)
+            .with_variant("Cons"))
+    );
+}
+
+#[test]
+fn should_decode_up_to_the_default_depth() {
+    let depth = DecodeLimits::DEFAULT_MAX_DEPTH - 1;
+    let bytes = hex::decode(list_hex(depth)).unwrap();
+    let value = List::from_plutus_bytes(&bytes).unwrap();
+    assert_eq!(value, list(&vec![1; depth]));
+    assert_eq!(value.to_plutus_bytes(), bytes);
+}
+
+#[test]
+fn should_reject_deep_input_without_overflowing() {
+    let bytes = hex::decode(list_hex(100_000)).unwrap();
+    let error = List::from_plutus_bytes(&bytes).unwrap_err();
+    assert_eq!(
+        error.kind(),
+        &DecodeErrorKind::LimitExceeded {
+            limit: Limit::Depth,
+            max: DecodeLimits::DEFAULT_MAX_DEPTH,
+        }
+    );
+
+    let mut bytes = vec![0x9f; 100_000];
+    bytes.extend(vec![0xff; 100_000]);
+    let error = PlutusData::from_plutus_bytes(&bytes).unwrap_err();
+    assert_eq!(
+        error,
+        DecodeError::limit_exceeded(Limit::Depth, DecodeLimits::DEFAULT_MAX_DEPTH)
+    );
+}
+
+#[test]
+fn should_decode_deeper_input_with_a_larger_stack() {
+    let bytes = hex::decode(list_hex(1000)).unwrap();
+    let decoded = std::thread::Builder::new()
+        .stack_size(64 << 20)
+        .spawn(move || {
+            let limits = DecodeLimits::max_depth(1001);
+            let value = List::from_plutus_bytes_with_limits(&bytes, limits).unwrap();
+            value.to_plutus_bytes() == bytes
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert!(decoded);
+}
+
+#[test]
+fn should_convert_deep_values_without_overflowing() {
+    let depth = 100_000;
+    let value = list(&vec![1; depth]);
+    assert_eq!(value.check_to_plutus(), Ok(()));
+
+    let data = value.to_plutus();
+    assert!(List::validate_plutus(&data).is_empty());
+    let value = List::from_plutus(data).unwrap();
+    assert!(matches!(value, List::Cons(1, _)));
+
+    let bytes = value.to_plutus_bytes();
+    assert_eq!(hex::encode(bytes), list_hex(depth));
+
+    let value = list(&vec![1; depth]);
+    let bytes = value.try_to_plutus_bytes().unwrap();
+    assert_eq!(hex::encode(bytes), list_hex(depth));
+
+    let mut data = create_array(vec![]);
+    for _ in 0..depth {
+        data = create_array(vec![data]);
+    }
+    assert_eq!(data.to_plutus_bytes().len(), 2 * depth + 1);
+}
@@ -0,0 +1,418 @@
+use std::collections::BTreeMap;
+
+use plutus_parser::{
+    AsPlutus, BigInt, Hash, PlutusData, PlutusTx,
+    ledger::{
+        Address, Credential, Datum, Input, Interval, IntervalBound, IntervalBoundType, Output,
+        OutputReference, Referenced, Value,
+    },
+    script_context::{v1, v2, v3},
+    testing::{assert_encodes_to_hex, assert_roundtrip},
+};
+
+fn key_hash(byte: u8) -> Hash<28> {
+    Hash::new([byte; 28])
+}
+
+fn address() -> Address {
+    Address {
+        payment_credential: Credential::VerificationKey(key_hash(0x11)),
+        stake_credential: Some(Referenced::Inline(Credential::Script(key_hash(0x22)))),
+    }
+}
+
+fn value(lovelace: i128) -> Value {
+    Value::from_lovelace(lovelace).with_asset(key_hash(0x33).to_vec(), b"TOKEN".to_vec(), 10)
+}
+
+fn valid_range() -> Interval {
+    Interval {
+        lower_bound: IntervalBound {
+            bound_type: IntervalBoundType::Finite(1_700_000_000_000),
+            is_inclusive: true,
+        },
+        upper_bound: IntervalBound {
+            bound_type: IntervalBoundType::PositiveInfinity,
+            is_inclusive: true,
+        },
+    }
+}
+
+fn datum() -> PlutusData {
+    PlutusData::BigInt(BigInt::Int(42.into()))
+}
+
+fn v1_out_ref() -> v1::TxOutRef {
+    v1::TxOutRef {
+        id: v1::TxId(Hash::new([0xaa; 32])),
+        index: 1,
+    }
+}
+
+fn v3_out_ref() -> v3::TxOutRef {
+    OutputReference {
+        transaction_id: Hash::new([0xaa; 32]),
+        output_index: 1,
+    }
+}
+
+fn v2_output() -> v2::TxOut {
+    Output {
+        address: address(),
+        value: value(2_000_000),
+        datum: Datum::InlineDatum(datum()),
+        reference_script: Some(key_hash(0x44)),
+    }
+}
+
+fn proposal() -> v3::ProposalProcedure {
+    v3::ProposalProcedure {
+        deposit: 100_000_000_000,
+        return_credential: Credential::VerificationKey(key_hash(0x11)),
+        governance_action: v3::GovernanceAction::UpdateCommittee {
+            previous: Some(v3::GovernanceActionId {
+                tx_id: Hash::new([0xbb; 32]),
+                index: 0,
+            }),
+            removed: vec![Credential::Script(key_hash(0x55))],
+            added: BTreeMap::from([(Credential::VerificationKey(key_hash(0x66)), 500)]),
+            quorum: v3::Rational {
+                numerator: 2,
+                denominator: 3,
+            },
+        },
+    }
+}
+
+#[test]
+fn should_encode_v1_tx_id_as_constr() {
+    assert_encodes_to_hex(
+        v1_out_ref(),
+        "d8799fd8799f5820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaff01ff",
+    );
+}
+
+#[test]
+fn should_encode_v3_tx_id_as_bytes() {
+    assert_encodes_to_hex(
+        v3_out_ref(),
+        "d8799f5820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa01ff",
+    );
+}
+
+#[test]
+fn should_encode_v1_tuples_as_constr() {
+    assert_encodes_to_hex(PlutusTx((1i64, 2i64)), "d8799f0102ff");
+}
+
+#[test]
+fn should_roundtrip_v1_script_context() {
+    let tx_info = v1::TxInfo {
+        inputs: vec![v1::TxInInfo {
+            out_ref: v1_out_ref(),
+            resolved: v1::TxOut {
+                address: address(),
+                value: value(5_000_000),
+                datum_hash: Some(Hash::new([0xcc; 32])),
+            },
+        }],
+        outputs: vec![v1::TxOut {
+            address: address(),
+            value: value(4_800_000),
+            datum_hash: None,
+        }],
+        fee: value(200_000),
+        mint: Value::zero().into(),
+        dcert: vec![
+            v1::DCert::DelegRegKey(Referenced::Inline(Credential::VerificationKey(key_hash(
+                0x11,
+            )))),
+            v1::DCert::DelegDelegate {
+                delegator: Referenced::Pointer {
+                    slot_number: 1,
+                    transaction_index: 2,
+                    certificate_index: 3,
+                },
+                delegatee: key_hash(0x77),
+            },
+            v1::DCert::PoolRetire {
+                pool_id: key_hash(0x77),
+                epoch: 400,
+            },
+            v1::DCert::Genesis,
+            v1::DCert::Mir,
+        ],
+        withdrawals: vec![(
+            Referenced::Inline(Credential::Script(key_hash(0x22))),
+            1_000,
+        )],
+        valid_range: valid_range(),
+        signatories: vec![key_hash(0x11)],
+        data: vec![(Hash::new([0xcc; 32]), datum())],
+        id: v1::TxId(Hash::new([0xdd; 32])),
+    };
+    for purpose in [
+        v1::ScriptPurpose::Minting(key_hash(0x33).to_vec()),
+        v1::ScriptPurpose::Spending(v1_out_ref()),
+        v1::ScriptPurpose::Rewarding(Referenced::Inline(Credential::Script(key_hash(0x22)))),
+        v1::ScriptPurpose::Certifying(v1::DCert::PoolRegister {
+            pool_id: key_hash(0x77),
+            pool_vrf: key_hash(0x78),
+        }),
+    ] {
+        assert_roundtrip(&v1::ScriptContext {
+            tx_info: tx_info.clone(),
+            purpose,
+        });
+    }
+}
+
+// laid out the way the ledger encodes a V1 context for a minting script, including
+// the zero ada entry it always puts in `mint`
+const V1_MINTING_CONTEXT: &str = concat!(
+    "d8799f",
+    "d8799f",
+    // inputs
+    "9fd8799f",
+    "d8799fd8799f5820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaff00ff",
+    "d8799fd8799fd8799f581c11111111111111111111111111111111111111111111111111111111ffd87a80ff",
+    "a140a1401a00989680d87a80ff",
+    "ffff",
+    // outputs
+    "9fd8799f",
+    "d8799fd8799f581c11111111111111111111111111111111111111111111111111111111ffd87a80ff",
+    "a240a1401a00958940581c33333333333333333333333333333333333333333333333333333333a145544f4b454e01",
+    "d87a80ffff",
+    // fee
+    "a140a1401a00030d40",
+    // mint
+    "a240a14000581c33333333333333333333333333333333333333333333333333333333a145544f4b454e01",
+    // dcert, withdrawals
+    "8080",
+    // valid range
+    "d8799fd8799fd87980d87a80ffd8799fd87b80d87a80ffff",
+    // signatories, data
+    "9f581c11111111111111111111111111111111111111111111111111111111ff80",
+    // id
+    "d8799f5820ddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddff",
+    "ff",
+    // purpose
+    "d8799f581c33333333333333333333333333333333333333333333333333333333ff",
+    "ff",
+);
+
+#[test]
+fn should_roundtrip_v1_mint_with_zero_ada() {
+    let bytes = hex::decode(V1_MINTING_CONTEXT).unwrap();
+    let context = v1::ScriptContext::from_plutus_bytes(&bytes).unwrap();
+    let mint = &context.tx_info.mint;
+    assert_eq!(mint.0[&vec![]], BTreeMap::from([(vec![], 0)]));
+    assert_eq!(
+        mint.to_value(),
+        Value::from_asset(key_hash(0x33), b"TOKEN".to_vec(), 1)
+    );
+    assert_eq!(v1::MintValue::from(mint.to_value()), context.tx_info.mint);
+    assert_eq!(context.tx_info.fee, Value::from_lovelace(200_000));
+    assert_eq!(hex::encode(context.to_plutus_bytes()), V1_MINTING_CONTEXT);
+}
+
+#[test]
+fn should_roundtrip_v2_script_context() {
+    let purpose = v2::ScriptPurpose::Spending(v1_out_ref());
+    let context = v2::ScriptContext {
+        tx_info: v2::TxInfo {
+            inputs: vec![v2::TxInInfo {
+                out_ref: v1_out_ref(),
+                resolved: v2_output(),
+            }],
+            reference_inputs: vec![v2::TxInInfo {
+                out_ref: v1::TxOutRef {
+                    id: v1::TxId(Hash::new([0xee; 32])),
+                    index: 0,
+                },
+                resolved: Output {
+                    datum: Datum::DatumHash(Hash::new([0xcc; 32])),
+                    reference_script: None,
+                    ..v2_output()
+                },
+            }],
+            outputs: vec![Output {
+                datum: Datum::NoDatum,
+                ..v2_output()
+            }],
+            fee: value(200_000),
+            mint: Value::from_asset(key_hash(0x33), b"TOKEN".to_vec(), -5).into(),
+            dcert: vec![v1::DCert::DelegDeRegKey(Referenced::Inline(
+                Credential::VerificationKey(key_hash(0x11)),
+            ))],
+            withdrawals: BTreeMap::from([(
+                Referenced::Inline(Credential::Script(key_hash(0x22))),
+                1_000,
+            )]),
+            valid_range: valid_range(),
+            signatories: vec![key_hash(0x11), key_hash(0x12)],
+            redeemers: BTreeMap::from([(purpose.clone(), datum())]),
+            data: BTreeMap::from([(Hash::new([0xcc; 32]), datum())]),
+            id: v1::TxId(Hash::new([0xdd; 32])),
+        },
+        purpose,
+    };
+    assert_roundtrip(&context);
+}
+
+#[test]
+fn should_roundtrip_v3_script_context() {
+    let voter = v3::Voter::DRep(Credential::Script(key_hash(0x88)));
+    let action = v3::GovernanceActionId {
+        tx_id: Hash::new([0xbb; 32]),
+        index: 1,
+    };
+    let certificates = vec![
+        v3::TxCert::RegStaking(Credential::VerificationKey(key_hash(0x11)), Some(2_000_000)),
+        v3::TxCert::UnRegStaking(Credential::VerificationKey(key_hash(0x11)), None),
+        v3::TxCert::DelegStaking(
+            Credential::VerificationKey(key_hash(0x11)),
+            v3::Delegatee::StakeVote(key_hash(0x77), v3::DRep::AlwaysAbstain),
+        ),
+        v3::TxCert::RegDeleg(
+            Credential::VerificationKey(key_hash(0x11)),
+            v3::Delegatee::Vote(v3::DRep::DRep(Credential::Script(key_hash(0x88)))),
+            2_000_000,
+        ),
+        v3::TxCert::RegDRep(Credential::Script(key_hash(0x88)), 500_000_000),
+        v3::TxCert::UpdateDRep(Credential::Script(key_hash(0x88))),
+        v3::TxCert::UnRegDRep(Credential::Script(key_hash(0x88)), 500_000_000),
+        v3::TxCert::PoolRegister {
+            pool_id: key_hash(0x77),
+            pool_vrf: key_hash(0x78),
+        },
+        v3::TxCert::PoolRetire {
+            pool_id: key_hash(0x77),
+            epoch: 400,
+        },
+        v3::TxCert::AuthHotCommittee(
+            Credential::Script(key_hash(0x55)),
+            Credential::VerificationKey(key_hash(0x56)),
+        ),
+        v3::TxCert::ResignColdCommittee(Credential::Script(key_hash(0x55))),
+    ];
+    let purposes = vec![
+        v3::ScriptPurpose::Minting(key_hash(0x33).to_vec()),
+        v3::ScriptPurpose::Spending(v3_out_ref()),
+        v3::ScriptPurpose::Rewarding(Credential::Script(key_hash(0x22))),
+        v3::ScriptPurpose::Certifying {
+            index: 0,
+            certificate: certificates[0].clone(),
+        },
+        v3::ScriptPurpose::Voting(voter.clone()),
+        v3::ScriptPurpose::Proposing {
+            index: 0,
+            procedure: proposal(),
+        },
+    ];
+    let tx_info = v3::TxInfo {
+        inputs: vec![Input {
+            output_reference: v3_out_ref(),
+            output: v2_output(),
+        }],
+        reference_inputs: vec![],
+        outputs: vec![v2_output()],
+        fee: 200_000,
+        mint: Value::zero(),
+        certificates,
+        withdrawals: BTreeMap::from([(Credential::Script(key_hash(0x22)), 1_000)]),
+        valid_range: valid_range(),
+        signatories: vec![key_hash(0x11)],
+        redeemers: purposes.into_iter().map(|p| (p, datum())).collect(),
+        data: BTreeMap::new(),
+        id: Hash::new([0xdd; 32]),
+        votes: BTreeMap::from([
+            (voter, BTreeMap::from([(action.clone(), v3::Vote::Yes)])),
+            (
+                v3::Voter::Committee(Credential::VerificationKey(key_hash(0x56))),
+                BTreeMap::from([(action.clone(), v3::Vote::Abstain)]),
+            ),
+            (
+                v3::Voter::StakePool(key_hash(0x77)),
+                BTreeMap::from([(action, v3::Vote::No)]),
+            ),
+        ]),
+        proposal_procedures: vec![
+            proposal(),
+            v3::ProposalProcedure {
+                governance_action: v3::GovernanceAction::ParameterChange {
+                    previous: None,
+                    parameters: datum(),
+                    guardrail: Some(key_hash(0x99)),
+                },
+                ..proposal()
+            },
+            v3::ProposalProcedure {
+                governance_action: v3::GovernanceAction::TreasuryWithdrawals {
+                    withdrawals: BTreeMap::from([(Credential::Script(key_hash(0x22)), 10)]),
+                    guardrail: None,
+                },
+                ..proposal()
+            },
+            v3::ProposalProcedure {
+                governance_action: v3::GovernanceAction::HardForkInitiation {
+                    previous: None,
+                    version: v3::ProtocolVersion {
+                        major: 10,
+                        minor: 0,
+                    },
+                },
+                ..proposal()
+            },
+            v3::ProposalProcedure {
+                governance_action: v3::GovernanceAction::NewConstitution {
+                    previous: None,
+                    constitution: v3::Constitution { script: None },
+                },
+                ..proposal()
+            },
+            v3::ProposalProcedure {
+                governance_action: v3::GovernanceAction::NoConfidence { previous: None },
+                ..proposal()
+            },
+            v3::ProposalProcedure {
+                governance_action: v3::GovernanceAction::Info,
+                ..proposal()
+            },
+        ],
+        current_treasury_amount: Some(1_000_000_000),
+        treasury_donation: None,
+    };
+    for script_info in [
+        v3::ScriptInfo::Minting(key_hash(0x33).to_vec()),
+        v3::ScriptInfo::Spending {
+            out_ref: v3_out_ref(),
+            datum: Some(datum()),
+        },
+        v3::ScriptInfo::Spending {
+            out_ref: v3_out_ref(),
+            datum: None,
+        },
+        v3::ScriptInfo::Rewarding(Credential::Script(key_hash(0x22))),
+        v3::ScriptInfo::Voting(v3::Voter::StakePool(key_hash(0x77))),
+        v3::ScriptInfo::Proposing {
+            index: 1,
+            procedure: proposal(),
+        },
+    ] {
+        assert_roundtrip(&v3::ScriptContext {
+            tx_info: tx_info.clone(),
+            redeemer: datum(),
+            script_info,
+        });
+    }
+}
+
+#[test]
+fn should_encode_v3_script_info_indices() {
+    let data = v3::ScriptInfo::Voting(v3::Voter::StakePool(key_hash(0x77))).to_plutus();
+    let (variant, fields) = plutus_parser::parse_constr(data).unwrap();
+    assert_eq!(variant, 4);
+    let (variant, _) = plutus_parser::parse_constr(fields[0].clone()).unwrap();
+    assert_eq!(variant, 2);
+}
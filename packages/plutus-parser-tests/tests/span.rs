@@ -0,0 +1,182 @@
+use std::collections::BTreeMap;
+
+use plutus_parser::{AsPlutus, DecodeError, PlutusData, Span, minicbor, render_hex};
+
+#[derive(AsPlutus, Debug, PartialEq, Eq)]
+struct Order {
+    owner: Vec<u8>,
+    amount: u64,
+    deadline: Option<u64>,
+    items: Vec<u32>,
+}
+
+#[derive(AsPlutus, Debug, PartialEq, Eq)]
+struct Common {
+    owner: Vec<u8>,
+    nonce: u8,
+}
+
+#[derive(AsPlutus, Debug, PartialEq, Eq)]
+struct Datum {
+    #[plutus(flatten)]
+    common: Common,
+    amounts: BTreeMap<Vec<u8>, u8>,
+}
+
+fn decode_err<T: AsPlutus + std::fmt::Debug>(hex: &str) -> (Vec<u8>, DecodeError) {
+    let bytes = hex::decode(hex).unwrap();
+    let error = T::from_plutus_bytes(&bytes).unwrap_err();
+    (bytes, error)
+}
+
+fn located(bytes: &[u8], error: &DecodeError) -> String {
+    let span = error.span().unwrap();
+    hex::encode(&bytes[span.offset..span.end()])
+}
+
+#[test]
+fn should_locate_invalid_fields() {
+    // amount is a bytestring
+    let (bytes, error) = decode_err::<Order>("d8799f41aa420102d87a8080ff");
+    assert_eq!(error.positions(), &[1]);
+    assert_eq!(error.span(), Some(Span { offset: 5, len: 3 }));
+    assert_eq!(located(&bytes, &error), "420102");
+}
+
+#[test]
+fn should_locate_values_in_options_and_lists() {
+    // deadline is Some(-1)
+    let (bytes, error) = decode_err::<Order>("d8799f41aa01d8799f20ff80ff");
+    assert_eq!(error.positions(), &[2, 0]);
+    assert_eq!(located(&bytes, &error), "20");
+
+    // the third item is a bytestring
+    let (bytes, error) = decode_err::<Order>("d8799f41aa01d87a808301024201ffff");
+    assert_eq!(error.positions(), &[3, 2]);
+    assert_eq!(located(&bytes, &error), "4201ff");
+}
+
+#[test]
+fn should_locate_map_keys_and_values() {
+    // the second value is 256
+    let (bytes, error) = decode_err::<Datum>("d8799f41aa01a24101014102190100ff");
+    assert_eq!(error.positions(), &[2, 3]);
+    assert_eq!(located(&bytes, &error), "190100");
+
+    // the second key is an integer
+    let (bytes, error) = decode_err::<Datum>("d8799f41aa01a2410101070aff");
+    assert_eq!(error.positions(), &[2, 2]);
+    assert_eq!(located(&bytes, &error), "07");
+}
+
+#[test]
+fn should_locate_flattened_fields() {
+    let (bytes, error) = decode_err::<Datum>("d8799f41aa190100a0ff");
+    assert_eq!(
+        error,
+        DecodeError::out_of_range(256)
+            .with_field_name("nonce")
+            .with_field_name("common")
+    );
+    assert_eq!(error.positions(), &[1]);
+    assert_eq!(located(&bytes, &error), "190100");
+}
+
+#[test]
+fn should_locate_fields_of_general_constructors() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(constr = 7)]
+    struct Big {
+        first: u8,
+        second: u8,
+    }
+
+    // tag 102, with fields [1, -1]
+    let (bytes, error) = decode_err::<Big>("d8668207820120");
+    assert_eq!(error.positions(), &[1]);
+    assert_eq!(error.span(), Some(Span { offset: 6, len: 1 }));
+    assert_eq!(located(&bytes, &error), "20");
+}
+
+#[test]
+fn should_locate_invalid_cbor() {
+    // a bignum holding a text string
+    let (_, error) = decode_err::<Order>("d8799f41aac26161ff");
+    assert!(error.positions().is_empty());
+    assert_eq!(error.span(), Some(Span { offset: 6, len: 1 }));
+}
+
+#[test]
+fn should_not_locate_errors_without_bytes() {
+    let data: PlutusData =
+        minicbor::decode(&hex::decode("d8799f41aa420102d87a8080ff").unwrap()).unwrap();
+    let error = Order::from_plutus(data).unwrap_err();
+    assert_eq!(error.positions(), &[1]);
+    assert_eq!(error.span(), None);
+}
+
+#[test]
+fn should_compare_errors_without_their_location() {
+    let (_, error) = decode_err::<Order>("d8799f41aa420102d87a8080ff");
+    assert_eq!(
+        error,
+        DecodeError::unexpected_type("BigInt", "BoundedBytes").with_field_name("amount")
+    );
+}
+
+#[test]
+fn should_render_a_hex_window() {
+    // the fourth item is an unexpected bytestring
+    let mut hex = String::from("d8799f41aa01d87a809f");
+    hex.push_str(&"01".repeat(20));
+    hex.push_str("43aabbcc");
+    hex.push_str(&"02".repeat(20));
+    hex.push_str("ffff");
+    let (bytes, error) = decode_err::<Order>(&hex);
+    assert_eq!(error.positions(), &[3, 20]);
+    assert_eq!(error.span(), Some(Span { offset: 30, len: 4 }));
+
+    let expected = concat!(
+        "00000000  d8 79 9f 41 aa 01 d8 7a 80 9f 01 01 01 01 01 01\n",
+        "00000010  01 01 01 01 01 01 01 01 01 01 01 01 01 01 43 aa\n",
+        "                                                    ^^ ^^\n",
+        "00000020  bb cc 02 02 02 02 02 02 02 02 02 02 02 02 02 02\n",
+        "          ^^ ^^\n",
+        "00000030  02 02 02 02 02 02 ff ff\n",
+    );
+    assert_eq!(error.render_hex(&bytes).unwrap(), expected);
+}
+
+#[test]
+fn should_elide_long_spans() {
+    let bytes = vec![0u8; 16 * 20];
+    let rendered = render_hex(
+        &bytes,
+        Span {
+            offset: 16,
+            len: 16 * 18,
+        },
+    );
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines[0], format!("00000000 {}", " 00".repeat(16)));
+    assert_eq!(lines[1], format!("00000010 {}", " 00".repeat(16)));
+    assert!(lines.contains(&"..."));
+    assert_eq!(lines.iter().filter(|l| l.contains("^^")).count(), 8);
+    assert_eq!(
+        lines.last(),
+        Some(&format!("00000130 {}", " 00".repeat(16)).as_str())
+    );
+}
+
+#[test]
+fn should_locate_fields_of_single_field_helios_structs() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(convention = "helios")]
+    struct Wrapper {
+        amount: u8,
+    }
+
+    let (bytes, error) = decode_err::<Vec<Wrapper>>("82011901ff");
+    assert_eq!(error.positions(), &[1]);
+    assert_eq!(located(&bytes, &error), "1901ff");
+}
@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::PathBuf;
+
+use plutus_parser::ledger::{Interval, IntervalBound, IntervalBoundType};
+use plutus_parser::testing::{
+    Golden, assert_encodes_to, assert_encodes_to_hex, assert_roundtrip, diagnostic,
+};
+use plutus_parser::{AsPlutus, create_constr};
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+struct Order {
+    owner: Vec<u8>,
+    side: Side,
+    amount: u64,
+    limit: Option<u64>,
+    tags: BTreeMap<String, i64>,
+    valid: Interval<u64>,
+}
+
+fn order() -> Order {
+    Order {
+        owner: vec![0xab; 4],
+        side: Side::Sell,
+        amount: 1_000_000,
+        limit: None,
+        tags: BTreeMap::from([("fee".into(), -3)]),
+        valid: Interval {
+            lower_bound: IntervalBound {
+                bound_type: IntervalBoundType::Finite(10),
+                is_inclusive: true,
+            },
+            upper_bound: IntervalBound {
+                bound_type: IntervalBoundType::PositiveInfinity,
+                is_inclusive: false,
+            },
+        },
+    }
+}
+
+fn golden() -> Golden {
+    Golden::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/golden"))
+}
+
+// a golden directory of its own for each test which writes files
+fn scratch(name: &str) -> Golden {
+    let dir: PathBuf = std::env::temp_dir().join(format!(
+        "plutus-parser-golden-{}-{name}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    Golden::new(dir)
+}
+
+fn panic_message(f: impl FnOnce()) -> String {
+    let payload = catch_unwind(AssertUnwindSafe(f)).unwrap_err();
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast::<&str>().unwrap().to_string(),
+    }
+}
+
+#[test]
+fn should_check_encodings() {
+    assert_roundtrip(&order());
+    assert_encodes_to(Side::Sell, create_constr(1, vec![]));
+    assert_encodes_to_hex(Side::Buy, "d87980");
+    assert_encodes_to_hex(vec![1u64, 2], "9f0102ff");
+}
+
+#[test]
+#[should_panic(expected = "unexpected encoding\n  expected: d87a80\n     found: d87980")]
+fn should_fail_for_different_encodings() {
+    // Buy and Sell would decode as each other
+    #[derive(Debug, PartialEq)]
+    struct AnySide(Side);
+    impl AsPlutus for AnySide {
+        fn from_plutus(
+            data: plutus_parser::PlutusData,
+        ) -> Result<Self, plutus_parser::DecodeError> {
+            Side::from_plutus(data).map(|_| AnySide(Side::Buy))
+        }
+        fn to_plutus(self) -> plutus_parser::PlutusData {
+            self.0.to_plutus()
+        }
+    }
+    assert_encodes_to_hex(AnySide(Side::Buy), "d87a80");
+}
+
+#[test]
+#[should_panic(expected = "failed to decode d87b80")]
+fn should_fail_for_undecodable_hex() {
+    assert_encodes_to_hex(Side::Buy, "d87b80");
+}
+
+#[test]
+fn should_render_diagnostic_notation() {
+    let diag = |hex: &str| diagnostic(&hex::decode(hex).unwrap()).unwrap();
+    assert_eq!(diag("d8799f0120ff"), "121([_ 1, -1])");
+    assert_eq!(diag("a1414101"), "{h'41': 1}");
+    assert_eq!(diag("5f4101420203ff"), "(_ h'01', h'0203')");
+    assert_eq!(diag("80"), "[]");
+    assert_eq!(diag("3bffffffffffffffff"), "-18446744073709551616");
+    assert_eq!(
+        diag("d87a9fd8799f01ffa10180ff"),
+        "122([_\n  121([_ 1]),\n  {\n    1: []\n  }\n])"
+    );
+    assert!(diagnostic(&[0x82, 0x01]).is_err());
+}
+
+#[test]
+fn should_match_golden_files() {
+    let golden = golden();
+    golden.check("order", &order());
+    golden.check("side_buy", &Side::Buy);
+    golden.check("pair", &(u64::MAX, i64::MIN));
+}
+
+#[test]
+fn should_show_diff_when_golden_files_change() {
+    let golden = scratch("diff");
+    golden.update("order", &order());
+
+    let changed = Order {
+        side: Side::Buy,
+        ..order()
+    };
+    let message = panic_message(|| golden.check("order", &changed));
+    assert!(message.starts_with("encoding of order doesn't match"));
+    assert!(
+        message
+            .contains("\n  121([_\n    h'abababab',\n-   122([]),\n+   121([]),\n    1000000,\n")
+    );
+    assert!(message.ends_with("run with UPDATE_GOLDEN=1 to accept the new encoding"));
+
+    golden.update("order", &changed);
+    golden.check("order", &changed);
+    std::fs::remove_dir_all(golden.path("order").parent().unwrap()).unwrap();
+}
+
+#[test]
+fn should_fail_for_missing_golden_files() {
+    let golden = scratch("missing");
+    let message = panic_message(|| golden.check("side", &Side::Buy));
+    assert!(message.contains("run with UPDATE_GOLDEN=1 to create it"));
+}
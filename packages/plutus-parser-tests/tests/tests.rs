@@ -1,16 +1,14 @@
 use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
 
+use plutus_parser::testing::assert_encodes_to;
 use plutus_parser::{
-    AsPlutus, BigInt, BoundedBytes, Constr, DecodeError, Hash, KeyValuePairs, MaybeIndefArray,
-    PlutusData, create_array, create_constr, create_map,
+    AsPlutus, AsPlutusFields, BigInt, BoundedBytes, Constr, DecodeError, DecodeErrorKind, Dict,
+    Hash, KeyValuePairs, MaybeIndefArray, Pairs, PathSegment, PlutusData, create_array,
+    create_constr, create_map,
 };
 use plutus_parser_tests::{Interval, IntervalBound, IntervalBoundType};
 
-fn assert_encoded<T: AsPlutus + std::fmt::Debug + Eq>(data: T, plutus: PlutusData) {
-    assert_eq!(data, T::from_plutus(plutus.clone()).unwrap());
-    assert_eq!(data.to_plutus(), plutus);
-}
-
 #[test]
 fn should_support_simple_struct() {
     #[derive(AsPlutus, Debug, PartialEq, Eq)]
@@ -36,29 +34,29 @@ fn should_support_simple_struct() {
             PlutusData::BoundedBytes(BoundedBytes::from(vec![0xca, 0xfe, 0xd0, 0x0d])),
         ],
     );
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
 fn should_support_optionals() {
-    assert_encoded(
+    assert_encodes_to(
         Some(1337),
         create_constr(0, vec![PlutusData::BigInt(BigInt::Int(1337.into()))]),
     );
-    assert_encoded(None::<u64>, create_constr(1, vec![]));
+    assert_encodes_to(None::<u64>, create_constr(1, vec![]));
 }
 
 #[test]
 fn should_support_enums() {
-    assert_encoded(
+    assert_encodes_to(
         IntervalBoundType::NegativeInfinity,
         create_constr(0, vec![]),
     );
-    assert_encoded(
+    assert_encodes_to(
         IntervalBoundType::Finite(13),
         create_constr(1, vec![PlutusData::BigInt(BigInt::Int(13.into()))]),
     );
-    assert_encoded(
+    assert_encodes_to(
         IntervalBoundType::PositiveInfinity,
         create_constr(2, vec![]),
     );
@@ -89,7 +87,7 @@ fn should_support_nested_structs() {
             ),
         ],
     );
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -107,7 +105,7 @@ fn should_support_tuple_structs() {
         ],
     );
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -119,7 +117,7 @@ fn should_support_tuples() {
         PlutusData::BigInt(BigInt::Int(9001.into())),
     ]);
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -140,7 +138,7 @@ fn should_support_arrays() {
         ]))],
     );
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -164,7 +162,7 @@ fn should_support_vec_u8_as_bytes() {
         ],
     );
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -183,7 +181,7 @@ fn should_support_hashes() {
         vec![PlutusData::BoundedBytes(BoundedBytes::from(vec![0x69; 28]))],
     );
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -203,7 +201,7 @@ fn should_support_maps() {
         ),
     ]);
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -217,7 +215,7 @@ fn should_support_custom_variants_for_structs() {
     let data = Special { is_special: true };
     let plutus = create_constr(2, vec![create_constr(1, vec![])]);
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -231,7 +229,7 @@ fn should_support_custom_variants_for_enums() {
     let data = Destination::Self_;
     let plutus = create_constr(1, vec![]);
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -249,7 +247,7 @@ fn should_support_structs_as_lists() {
     };
     let plutus = create_array(vec![create_constr(1, vec![]), create_constr(0, vec![])]);
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -261,7 +259,7 @@ fn should_support_empty_structs_as_lists() {
     let data = Listy;
     let plutus = create_array(vec![]);
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -276,7 +274,7 @@ fn should_support_tuple_structs_as_lists() {
         PlutusData::BigInt(BigInt::Int(1337.into())),
     ]);
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -318,7 +316,7 @@ fn should_support_multiple_enum_fields() {
     let data = Gollum::Smeagol(false, true);
     let plutus = create_constr(0, vec![create_constr(0, vec![]), create_constr(1, vec![])]);
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -356,7 +354,7 @@ fn should_support_plutus_data_fields() {
         )],
     );
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -389,7 +387,7 @@ fn should_support_plutus_primitive_fields() {
         ],
     );
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -409,7 +407,7 @@ fn should_support_array_fields() {
         ])],
     );
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -442,7 +440,7 @@ fn should_support_byte_array_fields() {
     let data = Basic { data: [0x67; 28] };
     let plutus = create_constr(0, vec![PlutusData::BoundedBytes(vec![0x67; 28].into())]);
 
-    assert_encoded(data, plutus);
+    assert_encodes_to(data, plutus);
 }
 
 #[test]
@@ -479,7 +477,9 @@ fn should_include_nested_field_names_in_errors() {
         )],
     );
 
-    let error = DecodeError::unexpected_variant(2).with_field_name("foo.bar");
+    let error = DecodeError::unexpected_variant(2)
+        .with_field_name("bar")
+        .with_field_name("foo");
 
     assert_eq!(Outer::from_plutus(plutus), Err(error));
 }
@@ -502,7 +502,9 @@ fn should_include_nested_field_indices_in_errors() {
         )],
     );
 
-    let error = DecodeError::unexpected_variant(2).with_field_name("foo.0");
+    let error = DecodeError::unexpected_variant(2)
+        .with_field_name(0)
+        .with_field_name("foo");
 
     assert_eq!(Outer::from_plutus(plutus), Err(error));
 }
@@ -546,7 +548,10 @@ fn should_include_array_indices_in_vec_errors() {
         ])],
     );
 
-    let error = DecodeError::wrong_variant_field_count(1, 0, 1).with_field_name("items[2].foo");
+    let error = DecodeError::wrong_variant_field_count(1, 0, 1)
+        .with_field_name("foo")
+        .with_index(2)
+        .with_field_name("items");
 
     assert_eq!(Collection::from_plutus(plutus), Err(error));
 }
@@ -590,7 +595,10 @@ fn should_include_array_indices_in_array_errors() {
         ])],
     );
 
-    let error = DecodeError::wrong_variant_field_count(1, 0, 1).with_field_name("items[2].foo");
+    let error = DecodeError::wrong_variant_field_count(1, 0, 1)
+        .with_field_name("foo")
+        .with_index(2)
+        .with_field_name("items");
 
     assert_eq!(Collection::from_plutus(plutus), Err(error));
 }
@@ -634,7 +642,10 @@ fn should_include_tuple_indices_in_errors() {
         ])],
     );
 
-    let error = DecodeError::wrong_variant_field_count(1, 0, 1).with_field_name("items.2.foo");
+    let error = DecodeError::wrong_variant_field_count(1, 0, 1)
+        .with_field_name("foo")
+        .with_field_name(2)
+        .with_field_name("items");
 
     assert_eq!(Collection::from_plutus(plutus), Err(error));
 }
@@ -663,7 +674,8 @@ fn should_include_map_key_indices_in_errors() {
     let error = DecodeError::custom(
         "error decoding string: invalid utf-8 sequence of 1 bytes from index 1",
     )
-    .with_field_name("by_name[(key #1)]");
+    .with_map_key(1)
+    .with_field_name("by_name");
 
     assert_eq!(Lookup::from_plutus(plutus), Err(error));
 }
@@ -694,12 +706,109 @@ fn should_include_map_value_indices_in_errors() {
         ])],
     );
 
-    let error =
-        DecodeError::unexpected_type("BigInt", "Constr").with_field_name("by_index[(value #1)].id");
+    let error = DecodeError::unexpected_type("BigInt", "Constr")
+        .with_field_name("id")
+        .with_map_value(1)
+        .with_field_name("by_index");
 
     assert_eq!(Values::from_plutus(plutus), Err(error));
 }
 
+#[test]
+fn should_expose_structured_error_paths() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Outer {
+        by_index: BTreeMap<u64, Vec<Inner>>,
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    enum Inner {
+        First { field: u8 },
+    }
+
+    let plutus = create_constr(
+        0,
+        vec![create_map(vec![(
+            PlutusData::BigInt(BigInt::Int(3.into())),
+            create_array(vec![
+                create_constr(0, vec![PlutusData::BigInt(BigInt::Int(1.into()))]),
+                create_constr(0, vec![PlutusData::BigInt(BigInt::Int(257.into()))]),
+            ]),
+        )])],
+    );
+
+    let error = Outer::from_plutus(plutus).unwrap_err();
+    assert_eq!(
+        error.path(),
+        [
+            PathSegment::Field("by_index".into()),
+            PathSegment::MapValue(0),
+            PathSegment::Index(1),
+            PathSegment::Variant("First".into()),
+            PathSegment::Field("field".into()),
+        ]
+    );
+    assert_eq!(
+        error.kind(),
+        &DecodeErrorKind::OutOfRange {
+            value: "257".into()
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "decode error at by_index[(value #0)][1]::First.field: value 257 out of range"
+    );
+}
+
+#[test]
+fn should_list_expected_variants_in_errors() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    enum OrderDatum {
+        Swap,
+        Deposit,
+        #[plutus(constr = 5)]
+        Withdraw,
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(list)]
+    struct Pair(u8, u8);
+
+    let error = OrderDatum::from_plutus(create_constr(4, vec![])).unwrap_err();
+    assert_eq!(
+        error.kind(),
+        &DecodeErrorKind::UnexpectedVariant {
+            variant: 4,
+            type_name: Some("OrderDatum"),
+            expected: &[(0, "Swap"), (1, "Deposit"), (5, "Withdraw")],
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "decode error at : unexpected variant 4 for OrderDatum (expected 0 Swap, 1 Deposit, 5 Withdraw)"
+    );
+
+    let error = OrderDatum::from_plutus(create_array(vec![])).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "decode error at : unexpected type for OrderDatum (expected Constr, found Array)"
+    );
+
+    let error = Pair::from_plutus(create_constr(0, vec![])).unwrap_err();
+    assert_eq!(
+        error,
+        DecodeError::unexpected_type("Array", "Constr").with_type_name("Pair")
+    );
+
+    // errors from inside a field keep the name of the innermost type
+    let error =
+        Vec::<OrderDatum>::from_plutus(create_array(vec![create_constr(9, vec![])])).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "decode error at [0]: unexpected variant 9 for OrderDatum (expected 0 Swap, 1 Deposit, 5 Withdraw)"
+    );
+}
+
 #[test]
 fn should_include_enum_variant_field_names_in_errors() {
     #[derive(AsPlutus, Debug, PartialEq, Eq)]
@@ -721,7 +830,10 @@ fn should_include_enum_variant_field_names_in_errors() {
         )],
     );
 
-    let error = DecodeError::out_of_range(257).with_field_name("inner::First.field");
+    let error = DecodeError::out_of_range(257)
+        .with_field_name("field")
+        .with_variant("First")
+        .with_field_name("inner");
 
     assert_eq!(Outer::from_plutus(plutus), Err(error));
 }
@@ -749,8 +861,851 @@ fn should_include_enum_variant_field_indices_in_errors() {
         )],
     );
 
-    let error =
-        DecodeError::out_of_range("0x010000000000000000").with_field_name("inner::Second.0");
+    let error = DecodeError::out_of_range("0x010000000000000000")
+        .with_field_name(0)
+        .with_variant("Second")
+        .with_field_name("inner");
 
     assert_eq!(Outer::from_plutus(plutus), Err(error));
 }
+
+#[test]
+fn should_infer_bounds_for_generic_structs() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Pair<A, B> {
+        a: A,
+        b: B,
+    }
+
+    let data = Pair {
+        a: "foo".to_string(),
+        b: Some(1337u64),
+    };
+    let plutus = create_constr(
+        0,
+        vec![
+            PlutusData::BoundedBytes(BoundedBytes::from("foo".as_bytes().to_vec())),
+            create_constr(0, vec![PlutusData::BigInt(BigInt::Int(1337.into()))]),
+        ],
+    );
+
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_infer_bounds_for_generic_enums() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    enum Either<L, R> {
+        Left(L),
+        Right { value: Vec<R> },
+    }
+
+    assert_encodes_to(
+        Either::<u8, String>::Left(3),
+        create_constr(0, vec![PlutusData::BigInt(BigInt::Int(3.into()))]),
+    );
+    assert_encodes_to(
+        Either::<u8, String>::Right {
+            value: vec!["bar".to_string()],
+        },
+        create_constr(
+            1,
+            vec![create_array(vec![PlutusData::BoundedBytes(
+                BoundedBytes::from("bar".as_bytes().to_vec()),
+            )])],
+        ),
+    );
+}
+
+#[test]
+fn should_infer_bounds_for_associated_types() {
+    trait Wire {
+        type Repr;
+    }
+
+    // only the associated type can be encoded
+    #[derive(Debug, PartialEq, Eq)]
+    struct Small;
+    impl Wire for Small {
+        type Repr = u16;
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Message<W: Wire> {
+        repr: W::Repr,
+        history: Vec<W::Repr>,
+        qualified: <W as Wire>::Repr,
+    }
+
+    let data = Message::<Small> {
+        repr: 12,
+        history: vec![3],
+        qualified: 4,
+    };
+    let plutus = create_constr(
+        0,
+        vec![
+            PlutusData::BigInt(BigInt::Int(12.into())),
+            create_array(vec![PlutusData::BigInt(BigInt::Int(3.into()))]),
+            PlutusData::BigInt(BigInt::Int(4.into())),
+        ],
+    );
+
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_skip_marked_fields() {
+    #[derive(Debug, PartialEq, Eq)]
+    struct NotPlutus;
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Tagged<T> {
+        id: u64,
+        #[plutus(skip)]
+        marker: PhantomData<T>,
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct TaggedTuple<T>(#[plutus(skip)] PhantomData<T>, u64);
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Cached {
+        id: u64,
+        #[plutus(skip)]
+        cache: Option<String>,
+    }
+
+    let data = Tagged::<NotPlutus> {
+        id: 7,
+        marker: PhantomData,
+    };
+    let plutus = create_constr(0, vec![PlutusData::BigInt(BigInt::Int(7.into()))]);
+    assert_encodes_to(data, plutus);
+
+    let data = TaggedTuple::<NotPlutus>(PhantomData, 7);
+    let plutus = create_constr(0, vec![PlutusData::BigInt(BigInt::Int(7.into()))]);
+    assert_encodes_to(data, plutus);
+
+    let plutus = create_constr(0, vec![PlutusData::BigInt(BigInt::Int(7.into()))]);
+    let data = Cached {
+        id: 7,
+        cache: Some("seven".into()),
+    };
+    assert_eq!(data.to_plutus(), plutus);
+    assert_eq!(
+        Cached::from_plutus(plutus),
+        Ok(Cached { id: 7, cache: None })
+    );
+}
+
+#[test]
+fn should_support_container_bounds() {
+    trait Wire {
+        type Repr: AsPlutus + std::fmt::Debug + PartialEq + Eq;
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Small;
+    impl Wire for Small {
+        type Repr = u8;
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(bound = "W: Wire")]
+    struct Message<W: Wire> {
+        repr: W::Repr,
+    }
+
+    let data = Message::<Small> { repr: 12 };
+    let plutus = create_constr(0, vec![PlutusData::BigInt(BigInt::Int(12.into()))]);
+
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_support_field_bounds() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Wrapper<T> {
+        #[plutus(bound = "Option<T>: AsPlutus")]
+        inner: Option<T>,
+        count: u64,
+    }
+
+    let data = Wrapper {
+        inner: Some(true),
+        count: 1,
+    };
+    let plutus = create_constr(
+        0,
+        vec![
+            create_constr(0, vec![create_constr(1, vec![])]),
+            PlutusData::BigInt(BigInt::Int(1.into())),
+        ],
+    );
+
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_run_container_validation() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(validate = "validate_order")]
+    struct Order {
+        min: u64,
+        max: u64,
+    }
+
+    fn validate_order(order: &Order) -> Result<(), String> {
+        if order.min > order.max {
+            return Err(format!("min {} is above max {}", order.min, order.max));
+        }
+        Ok(())
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Outer {
+        order: Order,
+    }
+
+    let valid = create_constr(
+        0,
+        vec![
+            PlutusData::BigInt(BigInt::Int(1.into())),
+            PlutusData::BigInt(BigInt::Int(2.into())),
+        ],
+    );
+    assert_encodes_to(Order { min: 1, max: 2 }, valid);
+
+    let invalid = create_constr(
+        0,
+        vec![create_constr(
+            0,
+            vec![
+                PlutusData::BigInt(BigInt::Int(3.into())),
+                PlutusData::BigInt(BigInt::Int(2.into())),
+            ],
+        )],
+    );
+    let error = DecodeError::validation("min 3 is above max 2").with_field_name("order");
+    assert_eq!(Outer::from_plutus(invalid), Err(error));
+}
+
+#[test]
+fn should_run_enum_validation() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(validate = "Amount::validate")]
+    enum Amount {
+        Fixed(u64),
+        Unlimited,
+    }
+
+    impl Amount {
+        fn validate(&self) -> Result<(), &'static str> {
+            match self {
+                Amount::Fixed(0) => Err("fixed amount must be positive"),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    assert_encodes_to(Amount::Unlimited, create_constr(1, vec![]));
+    let plutus = create_constr(0, vec![PlutusData::BigInt(BigInt::Int(0.into()))]);
+    let error = DecodeError::validation("fixed amount must be positive");
+    assert_eq!(Amount::from_plutus(plutus), Err(error));
+}
+
+#[test]
+fn should_check_field_ranges() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Fees {
+        #[plutus(range = 0..=10000)]
+        fee_bps: u16,
+    }
+
+    let plutus = create_constr(0, vec![PlutusData::BigInt(BigInt::Int(10000.into()))]);
+    assert_encodes_to(Fees { fee_bps: 10000 }, plutus);
+
+    let plutus = create_constr(0, vec![PlutusData::BigInt(BigInt::Int(10001.into()))]);
+    let error =
+        DecodeError::validation("value 10001 is not in range 0..=10000").with_field_name("fee_bps");
+    assert_eq!(Fees::from_plutus(plutus), Err(error));
+}
+
+#[test]
+fn should_check_field_lengths() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    enum Credential {
+        Key(#[plutus(len = 28)] Vec<u8>),
+    }
+
+    let plutus = create_constr(0, vec![PlutusData::BoundedBytes(vec![0x01; 28].into())]);
+    assert_encodes_to(Credential::Key(vec![0x01; 28]), plutus);
+
+    let plutus = create_constr(0, vec![PlutusData::BoundedBytes(vec![0x01; 27].into())]);
+    let error = DecodeError::validation("expected length 28, found 27")
+        .with_field_name(0)
+        .with_variant("Key");
+    assert_eq!(Credential::from_plutus(plutus), Err(error));
+}
+
+#[test]
+fn should_check_non_empty_fields() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Batch {
+        #[plutus(non_empty)]
+        orders: Vec<u64>,
+    }
+
+    let plutus = create_constr(
+        0,
+        vec![create_array(vec![PlutusData::BigInt(BigInt::Int(
+            1.into(),
+        ))])],
+    );
+    assert_encodes_to(Batch { orders: vec![1] }, plutus);
+
+    let plutus = create_constr(0, vec![create_array(vec![])]);
+    let error = DecodeError::validation("value is empty").with_field_name("orders");
+    assert_eq!(Batch::from_plutus(plutus), Err(error));
+}
+
+#[derive(AsPlutus, Debug, PartialEq, Eq)]
+struct CommonFields {
+    owner: Vec<u8>,
+    pool_id: Vec<u8>,
+    version: u8,
+}
+
+fn common_fields() -> (CommonFields, Vec<PlutusData>) {
+    let common = CommonFields {
+        owner: vec![0x01],
+        pool_id: vec![0x02],
+        version: 3,
+    };
+    let plutus = vec![
+        PlutusData::BoundedBytes(vec![0x01].into()),
+        PlutusData::BoundedBytes(vec![0x02].into()),
+        PlutusData::BigInt(BigInt::Int(3.into())),
+    ];
+    (common, plutus)
+}
+
+#[test]
+fn should_flatten_struct_fields() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct PoolDatum {
+        #[plutus(flatten)]
+        common: CommonFields,
+        fee: u64,
+    }
+
+    let (common, mut fields) = common_fields();
+    fields.push(PlutusData::BigInt(BigInt::Int(30.into())));
+
+    let data = PoolDatum { common, fee: 30 };
+    let plutus = create_constr(0, fields);
+
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_flatten_enum_variant_fields() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    enum Action {
+        Close(#[plutus(flatten)] CommonFields),
+        Update {
+            #[plutus(flatten)]
+            common: CommonFields,
+            fee: u64,
+        },
+    }
+
+    let (common, fields) = common_fields();
+    assert_encodes_to(Action::Close(common), create_constr(0, fields));
+
+    let (common, mut fields) = common_fields();
+    fields.push(PlutusData::BigInt(BigInt::Int(5.into())));
+    assert_encodes_to(Action::Update { common, fee: 5 }, create_constr(1, fields));
+}
+
+#[test]
+fn should_flatten_generic_fields() {
+    // only implements AsPlutusFields, so it can only be flattened
+    #[derive(Debug, PartialEq, Eq)]
+    struct Version(u8);
+
+    impl AsPlutusFields for Version {
+        const FIELD_COUNT: usize = 1;
+
+        fn from_plutus_fields(
+            fields: &mut impl Iterator<Item = PlutusData>,
+        ) -> Result<Self, DecodeError> {
+            let field = fields
+                .next()
+                .ok_or_else(|| DecodeError::custom("missing field"))?;
+            u8::from_plutus(field).map(Version)
+        }
+
+        fn to_plutus_fields(self, fields: &mut Vec<PlutusData>) {
+            fields.push(self.0.to_plutus());
+        }
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Envelope<T> {
+        id: u64,
+        #[plutus(flatten)]
+        body: T,
+    }
+
+    let (common, mut fields) = common_fields();
+    fields.insert(0, PlutusData::BigInt(BigInt::Int(1.into())));
+    assert_encodes_to(
+        Envelope {
+            id: 1,
+            body: common,
+        },
+        create_constr(0, fields),
+    );
+
+    let fields = vec![
+        PlutusData::BigInt(BigInt::Int(1.into())),
+        PlutusData::BigInt(BigInt::Int(2.into())),
+    ];
+    assert_encodes_to(
+        Envelope {
+            id: 1,
+            body: Version(2),
+        },
+        create_constr(0, fields),
+    );
+}
+
+#[test]
+fn should_flatten_fields_into_lists() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(list)]
+    struct Listed(u8, #[plutus(flatten)] CommonFields);
+
+    let (common, fields) = common_fields();
+    let data = Listed(9, common);
+    let mut plutus = vec![PlutusData::BigInt(BigInt::Int(9.into()))];
+    plutus.extend(fields);
+
+    assert_encodes_to(data, create_array(plutus));
+}
+
+#[test]
+fn should_include_flattened_field_names_in_errors() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct PoolDatum {
+        #[plutus(flatten)]
+        common: CommonFields,
+        fee: u64,
+    }
+
+    let plutus = create_constr(
+        0,
+        vec![
+            PlutusData::BigInt(BigInt::Int(1.into())),
+            PlutusData::BoundedBytes(vec![0x02].into()),
+            PlutusData::BigInt(BigInt::Int(3.into())),
+            PlutusData::BigInt(BigInt::Int(30.into())),
+        ],
+    );
+    let error = DecodeError::unexpected_type("BoundedBytes", "BigInt")
+        .with_field_name("owner")
+        .with_field_name("common");
+    assert_eq!(PoolDatum::from_plutus(plutus), Err(error));
+
+    let (_, fields) = common_fields();
+    let error = DecodeError::wrong_variant_field_count(0, 4, 3);
+    assert_eq!(PoolDatum::from_plutus(create_constr(0, fields)), Err(error));
+}
+
+#[test]
+fn should_validate_flattened_fields() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(validate = "validate_range")]
+    struct Range {
+        low: u64,
+        high: u64,
+    }
+
+    fn validate_range(range: &Range) -> Result<(), &'static str> {
+        if range.low > range.high {
+            return Err("range is inverted");
+        }
+        Ok(())
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Order {
+        #[plutus(flatten)]
+        range: Range,
+    }
+
+    let plutus = create_constr(
+        0,
+        vec![
+            PlutusData::BigInt(BigInt::Int(2.into())),
+            PlutusData::BigInt(BigInt::Int(1.into())),
+        ],
+    );
+    let error = DecodeError::validation("range is inverted").with_field_name("range");
+    assert_eq!(Order::from_plutus(plutus), Err(error));
+}
+
+#[test]
+fn should_use_explicit_field_indices() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Reordered {
+        #[plutus(index = 1)]
+        name: String,
+        #[plutus(index = 2)]
+        enabled: bool,
+        #[plutus(index = 0)]
+        id: u64,
+    }
+
+    let data = Reordered {
+        name: "foo".to_string(),
+        enabled: true,
+        id: 12,
+    };
+    let plutus = create_constr(
+        0,
+        vec![
+            PlutusData::BigInt(BigInt::Int(12.into())),
+            PlutusData::BoundedBytes(BoundedBytes::from("foo".as_bytes().to_vec())),
+            create_constr(1, vec![]),
+        ],
+    );
+
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_use_explicit_field_indices_in_tuples_and_variants() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(list)]
+    struct Swapped(#[plutus(index = 1)] u8, #[plutus(index = 0)] bool);
+
+    let data = Swapped(4, false);
+    let plutus = create_array(vec![
+        create_constr(0, vec![]),
+        PlutusData::BigInt(BigInt::Int(4.into())),
+    ]);
+    assert_encodes_to(data, plutus);
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(require_index)]
+    enum Pinned {
+        Empty,
+        Pair {
+            #[plutus(index = 1)]
+            first: u8,
+            #[plutus(index = 0)]
+            second: u8,
+        },
+    }
+
+    assert_encodes_to(Pinned::Empty, create_constr(0, vec![]));
+    let data = Pinned::Pair {
+        first: 1,
+        second: 2,
+    };
+    let plutus = create_constr(
+        1,
+        vec![
+            PlutusData::BigInt(BigInt::Int(2.into())),
+            PlutusData::BigInt(BigInt::Int(1.into())),
+        ],
+    );
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_use_explicit_field_indices_with_flattened_fields() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(require_index)]
+    struct PoolDatum {
+        #[plutus(index = 2)]
+        fee: u64,
+        #[plutus(index = 1, flatten)]
+        common: CommonFields,
+        #[plutus(index = 0)]
+        active: bool,
+    }
+
+    let (common, fields) = common_fields();
+    let mut plutus = vec![create_constr(1, vec![])];
+    plutus.extend(fields);
+    plutus.push(int(30));
+
+    let data = PoolDatum {
+        fee: 30,
+        common,
+        active: true,
+    };
+    assert_encodes_to(data, create_constr(0, plutus.clone()));
+
+    // positions count every flattened field
+    plutus[4] = create_constr(0, vec![]);
+    let error = PoolDatum::from_plutus(create_constr(0, plutus)).unwrap_err();
+    assert_eq!(error.positions(), &[4]);
+}
+
+fn int(value: i64) -> PlutusData {
+    PlutusData::BigInt(BigInt::Int(value.into()))
+}
+
+#[test]
+fn should_encode_dicts_in_key_order() {
+    let data: Dict<u64, u64> = [(3, 30), (1, 10), (2, 20)].into_iter().collect();
+    let plutus = create_map(vec![
+        (int(1), int(10)),
+        (int(2), int(20)),
+        (int(3), int(30)),
+    ]);
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_reject_unsorted_dicts() {
+    let plutus = create_map(vec![(int(2), int(20)), (int(1), int(10))]);
+    let error = DecodeError::validation("keys are not in ascending order").with_map_key(1);
+    assert_eq!(Dict::<u64, u64>::from_plutus(plutus), Err(error));
+
+    let plutus = create_map(vec![(int(1), int(10)), (int(1), int(20))]);
+    assert_eq!(
+        Dict::<u64, u64>::from_plutus(plutus),
+        Err(DecodeError::duplicate_key(1).with_map_key(1))
+    );
+}
+
+#[test]
+fn should_reject_duplicate_map_keys() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Lookup {
+        by_id: BTreeMap<u64, u64>,
+    }
+
+    let plutus = create_constr(
+        0,
+        vec![create_map(vec![
+            (int(1), int(10)),
+            (int(2), int(20)),
+            (int(1), int(30)),
+        ])],
+    );
+    let error = DecodeError::duplicate_key(2)
+        .with_map_key(2)
+        .with_field_name("by_id");
+    assert_eq!(Lookup::from_plutus(plutus), Err(error));
+
+    let plutus = create_map(vec![(int(1), int(10)), (int(1), int(30))]);
+    assert_eq!(
+        HashMap::<u64, u64>::from_plutus(plutus),
+        Err(DecodeError::duplicate_key(1).with_map_key(1))
+    );
+}
+
+#[test]
+fn should_encode_hash_maps_in_key_order() {
+    // sorted by encoded key, so shorter byte strings come first
+    let data: HashMap<Vec<u8>, u64> = HashMap::from([
+        (b"aa".to_vec(), 4),
+        (b"b".to_vec(), 2),
+        (vec![0, 0], 3),
+        (vec![], 0),
+        (b"a".to_vec(), 1),
+    ]);
+    let plutus = create_map(vec![
+        (PlutusData::BoundedBytes(vec![].into()), int(0)),
+        (PlutusData::BoundedBytes(b"a".to_vec().into()), int(1)),
+        (PlutusData::BoundedBytes(b"b".to_vec().into()), int(2)),
+        (PlutusData::BoundedBytes(vec![0, 0].into()), int(3)),
+        (PlutusData::BoundedBytes(b"aa".to_vec().into()), int(4)),
+    ]);
+    assert_eq!(data.clone().to_plutus(), plutus);
+    assert_eq!(
+        hex::encode(data.to_plutus_bytes()),
+        "a540004161014162024200000342616104"
+    );
+
+    // negative integers encode after every positive one
+    let data: HashMap<i64, u64> = HashMap::from([(-1, 3), (24, 2), (0, 0), (1, 1)]);
+    assert_eq!(hex::encode(data.to_plutus_bytes()), "a4000001011818022003");
+}
+
+#[test]
+fn should_preserve_pairs_order_and_duplicates() {
+    let data = Pairs::from(vec![(2u64, 20u64), (1, 10), (2, 21)]);
+    assert_eq!(data.get_first(&2), Some(&20));
+    assert_eq!(data.get_all(&2).collect::<Vec<_>>(), vec![&20, &21]);
+
+    let plutus = create_map(vec![
+        (int(2), int(20)),
+        (int(1), int(10)),
+        (int(2), int(21)),
+    ]);
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_encode_vec_of_tuples_as_pairs() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Votes {
+        #[plutus(pairs)]
+        votes: Vec<(Vec<u8>, bool)>,
+        tally: Vec<(u8, u8)>,
+    }
+
+    let data = Votes {
+        votes: vec![
+            (b"b".to_vec(), true),
+            (b"a".to_vec(), false),
+            (b"b".to_vec(), false),
+        ],
+        tally: vec![(1, 2)],
+    };
+    let plutus = create_constr(
+        0,
+        vec![
+            create_map(vec![
+                (
+                    PlutusData::BoundedBytes(BoundedBytes::from(b"b".to_vec())),
+                    create_constr(1, vec![]),
+                ),
+                (
+                    PlutusData::BoundedBytes(BoundedBytes::from(b"a".to_vec())),
+                    create_constr(0, vec![]),
+                ),
+                (
+                    PlutusData::BoundedBytes(BoundedBytes::from(b"b".to_vec())),
+                    create_constr(0, vec![]),
+                ),
+            ]),
+            create_array(vec![create_array(vec![int(1), int(2)])]),
+        ],
+    );
+    assert_encodes_to(data, plutus);
+
+    let plutus = create_constr(
+        0,
+        vec![
+            create_map(vec![(int(1), create_constr(0, vec![]))]),
+            create_array(vec![]),
+        ],
+    );
+    let error = DecodeError::unexpected_type("BoundedBytes", "BigInt")
+        .with_map_key(0)
+        .with_field_name("votes");
+    assert_eq!(Votes::from_plutus(plutus), Err(error));
+}
+
+#[test]
+fn should_encode_smart_pointers() {
+    assert_encodes_to(Box::new(5u64), int(5));
+    assert_encodes_to(std::rc::Rc::new(5u64), int(5));
+    assert_encodes_to(std::sync::Arc::new(5u64), int(5));
+    assert_encodes_to(
+        std::borrow::Cow::<[u8]>::Owned(vec![1, 2]),
+        PlutusData::BoundedBytes(BoundedBytes::from(vec![1, 2])),
+    );
+    assert_eq!(
+        std::borrow::Cow::Borrowed("hi").to_plutus(),
+        PlutusData::BoundedBytes(BoundedBytes::from(b"hi".to_vec()))
+    );
+}
+
+#[test]
+fn should_encode_void_and_non_zero() {
+    assert_encodes_to((), create_constr(0, vec![]));
+    assert_eq!(
+        <()>::from_plutus(create_constr(1, vec![])),
+        Err(DecodeError::unexpected_variant(1))
+    );
+
+    assert_encodes_to(std::num::NonZeroU64::new(3).unwrap(), int(3));
+    assert_encodes_to(std::num::NonZeroI32::new(-3).unwrap(), int(-3));
+    assert_eq!(
+        std::num::NonZeroU64::from_plutus(int(0)),
+        Err(DecodeError::out_of_range(0))
+    );
+}
+
+#[test]
+fn should_encode_large_tuples() {
+    let data = (
+        1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8, 8u8, 9u8, 10u8, 11u8, 12u8,
+    );
+    let plutus = create_array((1..=12).map(int).collect());
+    assert_encodes_to(data, plutus);
+}
+
+#[test]
+fn should_encode_sequences() {
+    let plutus = create_array(vec![int(3), int(1)]);
+    assert_encodes_to(std::collections::VecDeque::from([3u64, 1]), plutus.clone());
+    assert_encodes_to(smallvec::SmallVec::<[u64; 2]>::from_vec(vec![3, 1]), plutus);
+    assert_encodes_to(
+        smallvec::SmallVec::<[u8; 4]>::from_vec(vec![3, 1]),
+        PlutusData::BoundedBytes(BoundedBytes::from(vec![3, 1])),
+    );
+    assert_encodes_to(
+        bytes::Bytes::from_static(b"abc"),
+        PlutusData::BoundedBytes(BoundedBytes::from(b"abc".to_vec())),
+    );
+}
+
+#[test]
+fn should_encode_sets() {
+    let data = std::collections::BTreeSet::from([3u64, 1, 2]);
+    assert_encodes_to(data, create_array(vec![int(1), int(2), int(3)]));
+    assert_eq!(
+        std::collections::BTreeSet::<u64>::from_plutus(create_array(vec![int(2), int(1)])),
+        Err(DecodeError::validation("items are not in ascending order").with_index(1))
+    );
+    assert_eq!(
+        std::collections::BTreeSet::<u64>::from_plutus(create_array(vec![int(1), int(1)])),
+        Err(DecodeError::duplicate_item(1).with_index(1))
+    );
+
+    let data: std::collections::HashSet<u64> = (0..32).collect();
+    let mut expected: Vec<u64> = (0..32).collect();
+    expected.sort_by_key(|i| i.to_plutus_bytes());
+    assert_eq!(
+        data.clone().to_plutus(),
+        create_array(expected.into_iter().map(|i| int(i as i64)).collect())
+    );
+    assert_eq!(
+        std::collections::HashSet::<u64>::from_plutus(data.to_plutus()),
+        Ok((0..32).collect())
+    );
+    assert_eq!(
+        std::collections::HashSet::<u64>::from_plutus(create_array(vec![int(2), int(1), int(2)])),
+        Err(DecodeError::duplicate_item(2).with_index(2))
+    );
+}
+
+#[test]
+fn should_encode_either() {
+    type Choice = either::Either<u64, String>;
+    assert_encodes_to(Choice::Left(4), create_constr(0, vec![int(4)]));
+    assert_encodes_to(
+        Choice::Right("a".to_string()),
+        create_constr(
+            1,
+            vec![PlutusData::BoundedBytes(BoundedBytes::from(b"a".to_vec()))],
+        ),
+    );
+    assert_eq!(
+        Choice::from_plutus(create_constr(1, vec![int(4)])),
+        Err(DecodeError::unexpected_type("BoundedBytes", "BigInt")
+            .with_field_name(0)
+            .with_variant("Right"))
+    );
+}
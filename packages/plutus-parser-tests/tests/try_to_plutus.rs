@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+
+use plutus_parser::{
+    AsPlutus, EncodeError, EncodeErrorKind, Pairs, PathSegment, PlutusTx, TryToPlutus,
+};
+
+fn check_range(range: &Range) -> Result<(), String> {
+    if range.min > range.max {
+        return Err(format!("min {} is above max {}", range.min, range.max));
+    }
+    Ok(())
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+#[plutus(validate = "check_range")]
+struct Range {
+    min: u64,
+    max: u64,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+struct Order {
+    owner: Vec<u8>,
+    #[plutus(range = 0..=10000)]
+    fee_bps: u64,
+    #[plutus(non_empty)]
+    ranges: Vec<Range>,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+enum Action {
+    Place(Order),
+    Cancel { by_name: BTreeMap<String, Range> },
+}
+
+fn range(min: u64, max: u64) -> Range {
+    Range { min, max }
+}
+
+#[test]
+fn should_encode_valid_values() {
+    let order = Order {
+        owner: vec![0xaa],
+        fee_bps: 30,
+        ranges: vec![range(1, 2)],
+    };
+    assert_eq!(order.clone().try_to_plutus(), Ok(order.clone().to_plutus()));
+    assert_eq!(
+        order.clone().try_to_plutus_bytes(),
+        Ok(order.to_plutus_bytes())
+    );
+}
+
+#[test]
+fn should_run_the_validate_hook_on_encode() {
+    assert_eq!(
+        range(3, 2).try_to_plutus(),
+        Err(EncodeError::validation("min 3 is above max 2"))
+    );
+}
+
+#[test]
+fn should_check_field_constraints_on_encode() {
+    let order = Order {
+        owner: vec![],
+        fee_bps: 10001,
+        ranges: vec![range(1, 2)],
+    };
+    let error = order.try_to_plutus().unwrap_err();
+    assert_eq!(
+        error,
+        EncodeError::validation("value 10001 is not in range 0..=10000").with_field_name("fee_bps")
+    );
+    assert_eq!(
+        error.to_string(),
+        "encode error at fee_bps: validation failed: value 10001 is not in range 0..=10000"
+    );
+
+    let order = Order {
+        owner: vec![],
+        fee_bps: 0,
+        ranges: vec![],
+    };
+    assert_eq!(
+        order.try_to_plutus(),
+        Err(EncodeError::validation("value is empty").with_field_name("ranges"))
+    );
+}
+
+#[test]
+fn should_include_the_path_in_errors() {
+    let action = Action::Place(Order {
+        owner: vec![],
+        fee_bps: 0,
+        ranges: vec![range(1, 2), range(5, 4)],
+    });
+    let error = action.try_to_plutus().unwrap_err();
+    assert_eq!(
+        error.path(),
+        [
+            PathSegment::Variant("Place".into()),
+            PathSegment::Field("0".into()),
+            PathSegment::Field("ranges".into()),
+            PathSegment::Index(1),
+        ]
+    );
+    assert_eq!(
+        error.kind(),
+        &EncodeErrorKind::Validation("min 5 is above max 4".into())
+    );
+    assert_eq!(
+        error.to_string(),
+        "encode error at ::Place.0.ranges[1]: validation failed: min 5 is above max 4"
+    );
+
+    let action = Action::Cancel {
+        by_name: BTreeMap::from([("a".into(), range(1, 2)), ("b".into(), range(2, 1))]),
+    };
+    assert_eq!(
+        action.try_to_plutus(),
+        Err(EncodeError::validation("min 2 is above max 1")
+            .with_map_value(1)
+            .with_field_name("by_name")
+            .with_variant("Cancel"))
+    );
+}
+
+#[test]
+fn should_check_containers() {
+    let values = (Some(range(1, 2)), Pairs::from(vec![(range(4, 3), 1u8)]));
+    assert_eq!(
+        values.try_to_plutus(),
+        Err(EncodeError::validation("min 4 is above max 3")
+            .with_map_key(0)
+            .with_field_name(1))
+    );
+
+    let values = [Box::new(range(1, 2)), Box::new(range(2, 1))];
+    assert_eq!(
+        values.try_to_plutus(),
+        Err(EncodeError::validation("min 2 is above max 1").with_index(1))
+    );
+
+    let values = PlutusTx(vec![(0u8, range(2, 1))]);
+    assert_eq!(
+        values.try_to_plutus(),
+        Err(EncodeError::validation("min 2 is above max 1")
+            .with_field_name(1)
+            .with_index(0))
+    );
+}
+
+#[test]
+fn should_check_flattened_and_pairs_fields() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Datum {
+        #[plutus(flatten)]
+        range: Range,
+        #[plutus(pairs)]
+        limits: Vec<(u8, Range)>,
+    }
+
+    let datum = Datum {
+        range: range(2, 1),
+        limits: vec![],
+    };
+    assert_eq!(
+        datum.try_to_plutus(),
+        Err(EncodeError::validation("min 2 is above max 1").with_field_name("range"))
+    );
+
+    let datum = Datum {
+        range: range(1, 2),
+        limits: vec![(0, range(1, 2)), (0, range(9, 8))],
+    };
+    assert_eq!(
+        datum.try_to_plutus(),
+        Err(EncodeError::validation("min 9 is above max 8")
+            .with_map_value(1)
+            .with_field_name("limits"))
+    );
+}
+
+#[test]
+fn should_allow_hand_written_checks() {
+    #[derive(Debug, PartialEq, Eq)]
+    struct Even(u64);
+
+    impl AsPlutus for Even {
+        fn from_plutus(
+            data: plutus_parser::PlutusData,
+        ) -> Result<Self, plutus_parser::DecodeError> {
+            u64::from_plutus(data).map(Even)
+        }
+
+        fn to_plutus(self) -> plutus_parser::PlutusData {
+            self.0.to_plutus()
+        }
+
+        fn check_to_plutus(&self) -> Result<(), EncodeError> {
+            if !self.0.is_multiple_of(2) {
+                return Err(EncodeError::custom(format!("{} is odd", self.0)));
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Wrapper {
+        value: Even,
+    }
+
+    assert!(Wrapper { value: Even(2) }.try_to_plutus().is_ok());
+    assert_eq!(
+        Wrapper { value: Even(3) }.try_to_plutus(),
+        Err(EncodeError::custom("3 is odd").with_field_name("value"))
+    );
+}
@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+
+use plutus_parser::{
+    AsPlutus, AsPlutusFields, BigInt, DecodeError, PlutusData, create_array, create_constr,
+    create_map,
+};
+
+fn int(value: i64) -> PlutusData {
+    PlutusData::BigInt(BigInt::Int(value.into()))
+}
+
+fn bytes(value: &[u8]) -> PlutusData {
+    PlutusData::BoundedBytes(value.to_vec().into())
+}
+
+#[derive(AsPlutus, Debug, PartialEq, Eq)]
+struct Order {
+    owner: Vec<u8>,
+    amount: u64,
+    #[plutus(range = 0..=10000)]
+    fee_bps: u64,
+    items: Vec<Item>,
+}
+
+#[derive(AsPlutus, Debug, PartialEq, Eq)]
+enum Item {
+    Token { policy: Vec<u8>, quantity: i64 },
+    Lovelace(i64),
+}
+
+#[test]
+fn should_return_nothing_for_valid_data() {
+    let order = Order {
+        owner: vec![0xaa],
+        amount: 10,
+        fee_bps: 30,
+        items: vec![
+            Item::Token {
+                policy: vec![0xbb],
+                quantity: 1,
+            },
+            Item::Lovelace(2),
+        ],
+    };
+    assert_eq!(Order::validate_plutus(&order.to_plutus()), vec![]);
+}
+
+#[test]
+fn should_report_every_invalid_field() {
+    let plutus = create_constr(
+        0,
+        vec![
+            int(1),
+            bytes(&[0xaa]),
+            int(10001),
+            create_array(vec![
+                create_constr(0, vec![int(1), int(1)]),
+                create_constr(1, vec![int(2)]),
+                create_constr(2, vec![]),
+            ]),
+        ],
+    );
+
+    let errors = Order::validate_plutus(&plutus);
+    assert_eq!(
+        errors,
+        vec![
+            DecodeError::unexpected_type("BoundedBytes", "BigInt").with_field_name("owner"),
+            DecodeError::unexpected_type("BigInt", "BoundedBytes").with_field_name("amount"),
+            DecodeError::validation("value 10001 is not in range 0..=10000")
+                .with_field_name("fee_bps"),
+            DecodeError::unexpected_type("BoundedBytes", "BigInt")
+                .with_field_name("policy")
+                .with_variant("Token")
+                .with_index(0)
+                .with_field_name("items"),
+            DecodeError::unexpected_variant(2)
+                .with_type_name("Item")
+                .with_expected_variants(&[(0, "Token"), (1, "Lovelace")])
+                .with_index(2)
+                .with_field_name("items"),
+        ]
+    );
+
+    // the first error is the one from_plutus reports
+    assert_eq!(Order::from_plutus(plutus).unwrap_err(), errors[0]);
+}
+
+#[test]
+fn should_stop_at_values_with_the_wrong_shape() {
+    let plutus = create_constr(0, vec![int(1)]);
+    assert_eq!(
+        Order::validate_plutus(&plutus),
+        vec![DecodeError::wrong_variant_field_count(0, 4, 1)]
+    );
+
+    let plutus = create_constr(1, vec![]);
+    assert_eq!(
+        Order::validate_plutus(&plutus),
+        vec![
+            DecodeError::unexpected_variant(1)
+                .with_type_name("Order")
+                .with_expected_variants(&[(0, "Order")])
+        ]
+    );
+
+    assert_eq!(
+        Order::validate_plutus(&int(1)),
+        vec![DecodeError::unexpected_type("Constr", "BigInt").with_type_name("Order")]
+    );
+}
+
+#[test]
+fn should_report_every_invalid_map_entry() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Lookup {
+        by_name: BTreeMap<String, Option<u8>>,
+    }
+
+    let plutus = create_constr(
+        0,
+        vec![create_map(vec![
+            (bytes(b"a"), create_constr(0, vec![int(1)])),
+            (int(2), create_constr(0, vec![int(256)])),
+            (bytes(b"c"), create_constr(2, vec![])),
+        ])],
+    );
+
+    assert_eq!(
+        Lookup::validate_plutus(&plutus),
+        vec![
+            DecodeError::unexpected_type("BoundedBytes", "BigInt")
+                .with_map_key(1)
+                .with_field_name("by_name"),
+            DecodeError::out_of_range(256)
+                .with_map_value(1)
+                .with_field_name("by_name"),
+            DecodeError::unexpected_variant(2)
+                .with_map_value(2)
+                .with_field_name("by_name"),
+        ]
+    );
+}
+
+#[test]
+fn should_report_duplicate_keys_once_entries_are_valid() {
+    let plutus = create_map(vec![(int(1), int(1)), (int(1), int(2))]);
+    assert_eq!(
+        BTreeMap::<u8, u8>::validate_plutus(&plutus),
+        vec![DecodeError::duplicate_key(1).with_map_key(1)]
+    );
+}
+
+#[test]
+fn should_report_every_invalid_tuple_item() {
+    let plutus = create_array(vec![int(-1), bytes(&[]), int(3)]);
+    assert_eq!(
+        <(u8, u8, u8)>::validate_plutus(&plutus),
+        vec![
+            DecodeError::out_of_range(-1).with_field_name(0),
+            DecodeError::unexpected_type("BigInt", "BoundedBytes").with_field_name(1),
+        ]
+    );
+}
+
+#[test]
+fn should_run_the_validate_hook_once_fields_are_valid() {
+    fn check_range(range: &Range) -> Result<(), String> {
+        if range.min > range.max {
+            return Err(format!("min {} is above max {}", range.min, range.max));
+        }
+        Ok(())
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(validate = "check_range")]
+    struct Range {
+        min: u8,
+        max: u8,
+    }
+
+    let plutus = create_constr(0, vec![int(3), int(2)]);
+    assert_eq!(
+        Range::validate_plutus(&plutus),
+        vec![DecodeError::validation("min 3 is above max 2")]
+    );
+
+    let plutus = create_constr(0, vec![int(300), int(-1)]);
+    assert_eq!(
+        Range::validate_plutus(&plutus),
+        vec![
+            DecodeError::out_of_range(300).with_field_name("min"),
+            DecodeError::out_of_range(-1).with_field_name("max"),
+        ]
+    );
+}
+
+#[test]
+fn should_report_errors_in_flattened_fields() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Common {
+        owner: Vec<u8>,
+        nonce: u8,
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    struct Datum {
+        #[plutus(flatten)]
+        common: Common,
+        amount: u8,
+    }
+
+    let plutus = create_constr(0, vec![int(1), int(2), int(-3)]);
+    assert_eq!(
+        Datum::validate_plutus(&plutus),
+        vec![
+            DecodeError::unexpected_type("BoundedBytes", "BigInt")
+                .with_field_name("owner")
+                .with_field_name("common"),
+            DecodeError::out_of_range(-3).with_field_name("amount"),
+        ]
+    );
+
+    let fields = [bytes(&[]), int(256)];
+    assert_eq!(
+        Common::validate_plutus_fields(&mut fields.iter()),
+        vec![DecodeError::out_of_range(256).with_field_name("nonce")]
+    );
+}
+
+#[test]
+fn should_report_errors_with_conventions() {
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(convention = "plutustx")]
+    struct Pair {
+        value: (u8, u8),
+    }
+
+    #[derive(AsPlutus, Debug, PartialEq, Eq)]
+    #[plutus(convention = "helios")]
+    struct Wrapper {
+        amount: u8,
+    }
+
+    let plutus = create_constr(0, vec![create_constr(0, vec![int(256), int(-1)])]);
+    assert_eq!(
+        Pair::validate_plutus(&plutus),
+        vec![
+            DecodeError::out_of_range(256)
+                .with_field_name(0)
+                .with_field_name("value"),
+            DecodeError::out_of_range(-1)
+                .with_field_name(1)
+                .with_field_name("value"),
+        ]
+    );
+
+    assert_eq!(
+        Wrapper::validate_plutus(&int(256)),
+        vec![DecodeError::out_of_range(256).with_field_name("amount")]
+    );
+}
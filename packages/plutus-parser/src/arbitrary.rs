@@ -0,0 +1,104 @@
+//! Strategies for generating random plutus data with `proptest`.
+
+use proptest::prelude::*;
+
+use crate::{BigInt, BoundedBytes, Constr, KeyValuePairs, MaybeIndefArray, PlutusData};
+
+/// Bounds on the plutus data generated by `plutus_data`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DataParams {
+    /// How many levels of lists, maps and constructors to nest.
+    pub max_depth: u32,
+    /// The most items in each list or constructor, or entries in each map.
+    pub max_len: usize,
+    /// The longest byte string, including the bytes of big integers. Byte strings over
+    /// 64 bytes are encoded in chunks.
+    pub max_bytes_len: usize,
+}
+
+impl Default for DataParams {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_len: 4,
+            max_bytes_len: 80,
+        }
+    }
+}
+
+/// Generates any plutus data, with the default `DataParams`.
+pub fn any_plutus_data() -> BoxedStrategy<PlutusData> {
+    plutus_data(DataParams::default())
+}
+
+/// Generates plutus data within `params`. Lists, maps and constructor fields use both
+/// definite and indefinite length encodings, and constructors use every tag encoding.
+pub fn plutus_data(params: DataParams) -> BoxedStrategy<PlutusData> {
+    let DataParams {
+        max_depth,
+        max_len,
+        max_bytes_len,
+    } = params;
+    let leaf = prop_oneof![
+        big_int(max_bytes_len).prop_map(PlutusData::BigInt),
+        bounded_bytes(max_bytes_len).prop_map(PlutusData::BoundedBytes),
+    ];
+    let desired_size = (max_len as u32).saturating_pow(max_depth).max(1);
+    leaf.prop_recursive(max_depth, desired_size, max_len as u32, move |inner| {
+        let items = || prop::collection::vec(inner.clone(), 0..=max_len);
+        prop_oneof![
+            (constr_tag(), items(), any::<bool>()).prop_map(
+                |((tag, any_constructor), fields, definite)| {
+                    PlutusData::Constr(Constr {
+                        tag,
+                        any_constructor,
+                        fields: maybe_indef(fields, definite),
+                    })
+                }
+            ),
+            (items(), any::<bool>())
+                .prop_map(|(items, definite)| PlutusData::Array(maybe_indef(items, definite))),
+            (
+                prop::collection::vec((inner.clone(), inner.clone()), 0..=max_len),
+                any::<bool>()
+            )
+                .prop_map(|(kvps, definite)| {
+                    PlutusData::Map(if definite {
+                        KeyValuePairs::Def(kvps)
+                    } else {
+                        KeyValuePairs::Indef(kvps)
+                    })
+                }),
+        ]
+    })
+    .boxed()
+}
+
+fn bounded_bytes(max_len: usize) -> impl Strategy<Value = BoundedBytes> {
+    prop::collection::vec(any::<u8>(), 0..=max_len).prop_map(BoundedBytes::from)
+}
+
+fn big_int(max_bytes_len: usize) -> impl Strategy<Value = BigInt> {
+    prop_oneof![
+        4 => any::<i64>().prop_map(|value| BigInt::Int(value.into())),
+        1 => bounded_bytes(max_bytes_len).prop_map(BigInt::BigUInt),
+        1 => bounded_bytes(max_bytes_len).prop_map(BigInt::BigNInt),
+    ]
+}
+
+// the tag and, for the general encoding, the constructor index
+fn constr_tag() -> impl Strategy<Value = (u64, Option<u64>)> {
+    prop_oneof![
+        (121u64..=127).prop_map(|tag| (tag, None)),
+        (1280u64..=1400).prop_map(|tag| (tag, None)),
+        any::<u64>().prop_map(|variant| (102, Some(variant))),
+    ]
+}
+
+fn maybe_indef(items: Vec<PlutusData>, definite: bool) -> MaybeIndefArray<PlutusData> {
+    if definite {
+        MaybeIndefArray::Def(items)
+    } else {
+        MaybeIndefArray::Indef(items)
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    borrow::Borrow,
+    collections::BTreeMap,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    AsPlutus, DecodeError, EncodeError, PlutusData, check_entries, create_map, or_decode_error,
+    parse_map, validate_entries,
+};
+
+/// A map with unique keys, always encoded in ascending key order, like Aiken's `Dict`.
+/// Decoding rejects maps whose keys are out of order or repeated.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Dict<K, V>(BTreeMap<K, V>);
+
+impl<K: Ord, V> Dict<K, V> {
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.0.insert(key, value)
+    }
+
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.0.remove(key)
+    }
+
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.0.get(key)
+    }
+
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.0.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.0.iter()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.0.keys()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.0.values()
+    }
+
+    pub fn into_inner(self) -> BTreeMap<K, V> {
+        self.0
+    }
+}
+
+impl<K: Ord, V> Default for Dict<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> From<BTreeMap<K, V>> for Dict<K, V> {
+    fn from(map: BTreeMap<K, V>) -> Self {
+        Self(map)
+    }
+}
+
+impl<K: Ord, V, const N: usize> From<[(K, V); N]> for Dict<K, V> {
+    fn from(entries: [(K, V); N]) -> Self {
+        Self(BTreeMap::from(entries))
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for Dict<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<K, V> IntoIterator for Dict<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::collections::btree_map::IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<K: AsPlutus + Ord, V: AsPlutus> AsPlutus for Dict<K, V> {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        let mut map = BTreeMap::new();
+        for (index, (key, value)) in decode_keys::<K>(data, true)?.into_iter().enumerate() {
+            let value = V::from_plutus(value).map_err(|e| e.with_map_value(index))?;
+            map.insert(key, value);
+        }
+        Ok(Self(map))
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        let kvps = self
+            .0
+            .into_iter()
+            .map(|(k, v)| (k.to_plutus(), v.to_plutus()))
+            .collect();
+        create_map(kvps)
+    }
+
+    // unsorted and repeated keys are found by decoding once every entry is valid
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        or_decode_error::<Self>(validate_entries::<K, V>(data), data)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        check_entries(&self.0, K::check_to_plutus, V::check_to_plutus)
+    }
+}
+
+/// An association list which keeps its entries in order and may repeat keys, like
+/// Aiken's `Pairs`. It is encoded as a map, but behaves like a `Vec<(K, V)>`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pairs<K, V>(Vec<(K, V)>);
+
+impl<K, V> Pairs<K, V> {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// The value of the first entry with the given key.
+    pub fn get_first<Q: PartialEq + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.0
+            .iter()
+            .find(|(k, _)| k.borrow() == key)
+            .map(|(_, v)| v)
+    }
+
+    /// The values of every entry with the given key, in order.
+    pub fn get_all<'a, Q: PartialEq + ?Sized>(&'a self, key: &'a Q) -> impl Iterator<Item = &'a V>
+    where
+        K: Borrow<Q>,
+    {
+        self.0
+            .iter()
+            .filter(move |(k, _)| k.borrow() == key)
+            .map(|(_, v)| v)
+    }
+
+    pub fn into_inner(self) -> Vec<(K, V)> {
+        self.0
+    }
+}
+
+impl<K, V> Default for Pairs<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Deref for Pairs<K, V> {
+    type Target = Vec<(K, V)>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K, V> DerefMut for Pairs<K, V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K, V> From<Vec<(K, V)>> for Pairs<K, V> {
+    fn from(pairs: Vec<(K, V)>) -> Self {
+        Self(pairs)
+    }
+}
+
+impl<K, V> From<Pairs<K, V>> for Vec<(K, V)> {
+    fn from(pairs: Pairs<K, V>) -> Self {
+        pairs.0
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for Pairs<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<K, V> IntoIterator for Pairs<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<K: AsPlutus, V: AsPlutus> AsPlutus for Pairs<K, V> {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        parse_map(data)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, (key, value))| {
+                let key = K::from_plutus(key).map_err(|e| e.with_map_key(index))?;
+                let value = V::from_plutus(value).map_err(|e| e.with_map_value(index))?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        let kvps = self
+            .0
+            .into_iter()
+            .map(|(k, v)| (k.to_plutus(), v.to_plutus()))
+            .collect();
+        create_map(kvps)
+    }
+
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        validate_entries::<K, V>(data)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        check_entries(
+            self.0.iter().map(|(k, v)| (k, v)),
+            K::check_to_plutus,
+            V::check_to_plutus,
+        )
+    }
+}
+
+/// Decodes the keys of a map, leaving the values as they are. When `sorted` is set,
+/// the keys must be in strictly ascending order.
+pub(crate) fn decode_keys<K: AsPlutus + Ord>(
+    data: PlutusData,
+    sorted: bool,
+) -> Result<Vec<(K, PlutusData)>, DecodeError> {
+    let mut entries: Vec<(K, PlutusData)> = vec![];
+    for (index, (key, value)) in parse_map(data)?.into_iter().enumerate() {
+        let key = K::from_plutus(key).map_err(|e| e.with_map_key(index))?;
+        if sorted && let Some((previous, _)) = entries.last() {
+            if *previous == key {
+                return Err(DecodeError::duplicate_key(index).with_map_key(index));
+            }
+            if *previous > key {
+                return Err(
+                    DecodeError::validation("keys are not in ascending order").with_map_key(index)
+                );
+            }
+        }
+        entries.push((key, value));
+    }
+    Ok(entries)
+}
@@ -0,0 +1,12 @@
+//! Types from the Aiken standard library's `cardano` modules, encoded exactly like
+//! their Aiken counterparts.
+
+mod address;
+mod assets;
+mod interval;
+mod transaction;
+
+pub use address::*;
+pub use assets::*;
+pub use interval::*;
+pub use transaction::*;
@@ -0,0 +1,261 @@
+use plutus_parser_derive::AsPlutus;
+use thiserror::Error;
+
+use crate::Hash;
+
+pub type VerificationKeyHash = Hash<28>;
+pub type ScriptHash = Hash<28>;
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Credential {
+    VerificationKey(VerificationKeyHash),
+    Script(ScriptHash),
+}
+
+pub type PaymentCredential = Credential;
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Referenced<T> {
+    Inline(T),
+    Pointer {
+        slot_number: u64,
+        transaction_index: u64,
+        certificate_index: u64,
+    },
+}
+
+pub type StakeCredential = Referenced<Credential>;
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Address {
+    pub payment_credential: PaymentCredential,
+    pub stake_credential: Option<StakeCredential>,
+}
+
+/// The network an address belongs to, taken from the low bits of its header byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Network {
+    Testnet,
+    Mainnet,
+}
+
+impl Network {
+    pub fn id(self) -> u8 {
+        match self {
+            Self::Testnet => 0,
+            Self::Mainnet => 1,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Result<Self, AddressError> {
+        match id {
+            0 => Ok(Self::Testnet),
+            1 => Ok(Self::Mainnet),
+            id => Err(AddressError::UnknownNetwork(id)),
+        }
+    }
+
+    /// The human-readable part of bech32 payment addresses on this network.
+    pub fn address_prefix(self) -> &'static str {
+        match self {
+            Self::Testnet => "addr_test",
+            Self::Mainnet => "addr",
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum AddressError {
+    #[error("invalid bech32: {0}")]
+    InvalidBech32(String),
+    #[error("unexpected bech32 prefix {0}")]
+    UnexpectedPrefix(String),
+    #[error("bech32 prefix {prefix} does not match network {network:?}")]
+    NetworkMismatch { prefix: String, network: Network },
+    #[error("address is empty")]
+    Empty,
+    #[error("Byron addresses cannot be represented as Plutus data")]
+    Byron,
+    #[error("reward addresses cannot be represented as Plutus data")]
+    Reward,
+    #[error("unknown address type {0}")]
+    UnknownType(u8),
+    #[error("unknown network id {0}")]
+    UnknownNetwork(u8),
+    #[error("unexpected length for address (expected {expected}, found {actual})")]
+    WrongLength { expected: usize, actual: usize },
+    #[error("invalid stake pointer")]
+    InvalidPointer,
+}
+
+impl Address {
+    /// Encodes the address in the binary format used by the ledger (CIP-19).
+    pub fn to_shelley_bytes(&self, network: Network) -> Vec<u8> {
+        let (payment_type, payment_hash) = match &self.payment_credential {
+            Credential::VerificationKey(hash) => (0, hash),
+            Credential::Script(hash) => (1, hash),
+        };
+        let mut bytes = vec![];
+        let address_type = match &self.stake_credential {
+            Some(Referenced::Inline(Credential::VerificationKey(_))) => payment_type,
+            Some(Referenced::Inline(Credential::Script(_))) => 0b0010 | payment_type,
+            Some(Referenced::Pointer { .. }) => 0b0100 | payment_type,
+            None => 0b0110 | payment_type,
+        };
+        bytes.push(address_type << 4 | network.id());
+        bytes.extend_from_slice(payment_hash.as_slice());
+        match &self.stake_credential {
+            Some(Referenced::Inline(
+                Credential::VerificationKey(hash) | Credential::Script(hash),
+            )) => bytes.extend_from_slice(hash.as_slice()),
+            Some(Referenced::Pointer {
+                slot_number,
+                transaction_index,
+                certificate_index,
+            }) => {
+                for value in [slot_number, transaction_index, certificate_index] {
+                    write_variable_nat(&mut bytes, *value);
+                }
+            }
+            None => {}
+        }
+        bytes
+    }
+
+    /// Decodes an address in the binary format used by the ledger (CIP-19), along with
+    /// the network it belongs to.
+    pub fn from_shelley_bytes(bytes: &[u8]) -> Result<(Network, Self), AddressError> {
+        let (&header, rest) = bytes.split_first().ok_or(AddressError::Empty)?;
+        let address_type = header >> 4;
+        match address_type {
+            0b1000 => return Err(AddressError::Byron),
+            0b1110 | 0b1111 => return Err(AddressError::Reward),
+            0b1001.. => return Err(AddressError::UnknownType(address_type)),
+            _ => {}
+        }
+        let network = Network::from_id(header & 0x0f)?;
+        let expected = match address_type >> 1 {
+            0b00 | 0b01 => Some(57),
+            0b11 => Some(29),
+            _ => None,
+        };
+        if let Some(expected) = expected
+            && bytes.len() != expected
+        {
+            return Err(AddressError::WrongLength {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+        if rest.len() < 28 {
+            return Err(AddressError::WrongLength {
+                expected: 29,
+                actual: bytes.len(),
+            });
+        }
+
+        let (payment_hash, rest) = rest.split_at(28);
+        let payment_hash = Hash::new(payment_hash.try_into().expect("length checked"));
+        let payment_credential = if address_type & 0b0001 == 0 {
+            Credential::VerificationKey(payment_hash)
+        } else {
+            Credential::Script(payment_hash)
+        };
+        let stake_credential = match address_type >> 1 {
+            0b00 | 0b01 => {
+                let stake_hash = Hash::new(rest.try_into().expect("length checked"));
+                Some(Referenced::Inline(if address_type & 0b0010 == 0 {
+                    Credential::VerificationKey(stake_hash)
+                } else {
+                    Credential::Script(stake_hash)
+                }))
+            }
+            0b10 => {
+                let mut rest = rest;
+                let slot_number = read_variable_nat(&mut rest)?;
+                let transaction_index = read_variable_nat(&mut rest)?;
+                let certificate_index = read_variable_nat(&mut rest)?;
+                if !rest.is_empty() {
+                    return Err(AddressError::InvalidPointer);
+                }
+                Some(Referenced::Pointer {
+                    slot_number,
+                    transaction_index,
+                    certificate_index,
+                })
+            }
+            _ => None,
+        };
+        Ok((
+            network,
+            Self {
+                payment_credential,
+                stake_credential,
+            },
+        ))
+    }
+}
+
+#[cfg(feature = "bech32")]
+impl Address {
+    pub fn to_bech32(&self, network: Network) -> String {
+        let hrp = bech32::Hrp::parse_unchecked(network.address_prefix());
+        bech32::encode::<bech32::Bech32>(hrp, &self.to_shelley_bytes(network))
+            .expect("addresses are short enough for bech32")
+    }
+
+    /// Decodes a bech32 payment address such as `addr1...` or `addr_test1...`, along
+    /// with the network it belongs to.
+    pub fn from_bech32(address: &str) -> Result<(Network, Self), AddressError> {
+        let (hrp, bytes) = match bech32::decode(address) {
+            Ok(decoded) => decoded,
+            // Byron addresses are base58, and start with one of these prefixes
+            Err(_) if address.starts_with("Ae2") || address.starts_with("DdzFF") => {
+                return Err(AddressError::Byron);
+            }
+            Err(error) => return Err(AddressError::InvalidBech32(error.to_string())),
+        };
+        let prefix = hrp.as_str();
+        if prefix.starts_with("stake") {
+            return Err(AddressError::Reward);
+        }
+        if prefix != "addr" && prefix != "addr_test" {
+            return Err(AddressError::UnexpectedPrefix(prefix.to_string()));
+        }
+        let (network, address) = Self::from_shelley_bytes(&bytes)?;
+        if network.address_prefix() != prefix {
+            return Err(AddressError::NetworkMismatch {
+                prefix: prefix.to_string(),
+                network,
+            });
+        }
+        Ok((network, address))
+    }
+}
+
+// pointers are made of naturals in big-endian base 128, with the high bit set on
+// every byte but the last
+fn write_variable_nat(bytes: &mut Vec<u8>, mut value: u64) {
+    let mut encoded = vec![(value & 0x7f) as u8];
+    value >>= 7;
+    while value > 0 {
+        encoded.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.extend(encoded.into_iter().rev());
+}
+
+fn read_variable_nat(bytes: &mut &[u8]) -> Result<u64, AddressError> {
+    let mut value: u64 = 0;
+    loop {
+        let (&byte, rest) = bytes.split_first().ok_or(AddressError::InvalidPointer)?;
+        *bytes = rest;
+        if value.leading_zeros() < 7 {
+            return Err(AddressError::InvalidPointer);
+        }
+        value = value << 7 | u64::from(byte & 0x7f);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
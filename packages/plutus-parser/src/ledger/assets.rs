@@ -0,0 +1,236 @@
+use std::{
+    collections::BTreeMap,
+    ops::{Add, Neg, Sub},
+};
+
+use crate::{AsPlutus, DecodeError, Hash, PlutusData, collections::decode_keys, create_map};
+
+pub type PolicyId = Hash<28>;
+pub type AssetName = Vec<u8>;
+
+/// Quantities of each asset, by policy id and asset name, following the invariants
+/// of Aiken's `assets` module: quantities are never zero, policies never have an
+/// empty set of assets, and everything is encoded in ascending key order. Ada uses
+/// an empty policy id and asset name, which is why policies are raw bytes here.
+///
+/// Quantities are `i128`s, so sums of any on-chain quantities fit. Arithmetic is
+/// still checked: the `checked_` methods and the `+`, `-` and negation operators
+/// return `None` if a quantity overflows.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Value {
+    assets: BTreeMap<Vec<u8>, BTreeMap<AssetName, i128>>,
+}
+
+impl Value {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn from_lovelace(quantity: i128) -> Self {
+        Self::zero().with_lovelace(quantity)
+    }
+
+    pub fn from_asset(
+        policy_id: PolicyId,
+        asset_name: impl Into<AssetName>,
+        quantity: i128,
+    ) -> Self {
+        Self::zero().with_asset(policy_id.to_vec(), asset_name, quantity)
+    }
+
+    pub fn lovelace(&self) -> i128 {
+        self.quantity_of(&[], &[])
+    }
+
+    pub fn with_lovelace(self, quantity: i128) -> Self {
+        self.with_asset(vec![], vec![], quantity)
+    }
+
+    pub fn without_lovelace(mut self) -> Self {
+        self.assets.remove([].as_slice());
+        self
+    }
+
+    /// Sets the quantity of a single asset, removing it if `quantity` is zero.
+    pub fn with_asset(
+        mut self,
+        policy_id: impl Into<Vec<u8>>,
+        asset_name: impl Into<AssetName>,
+        quantity: i128,
+    ) -> Self {
+        self.set_quantity(policy_id.into(), asset_name.into(), quantity);
+        self
+    }
+
+    pub fn quantity_of(&self, policy_id: &[u8], asset_name: &[u8]) -> i128 {
+        self.assets
+            .get(policy_id)
+            .and_then(|assets| assets.get(asset_name))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Adds `quantity` of a single asset, removing it if its quantity drops to zero.
+    pub fn checked_add(
+        mut self,
+        policy_id: impl Into<Vec<u8>>,
+        asset_name: impl Into<AssetName>,
+        quantity: i128,
+    ) -> Option<Self> {
+        self.add_quantity(policy_id.into(), asset_name.into(), quantity)?;
+        Some(self)
+    }
+
+    pub fn checked_merge(mut self, other: Value) -> Option<Self> {
+        for (policy_id, asset_name, quantity) in other.into_flat() {
+            self.add_quantity(policy_id, asset_name, quantity)?;
+        }
+        Some(self)
+    }
+
+    pub fn checked_sub(self, other: Value) -> Option<Self> {
+        self.checked_merge(other.checked_negate()?)
+    }
+
+    pub fn checked_negate(mut self) -> Option<Self> {
+        for assets in self.assets.values_mut() {
+            for quantity in assets.values_mut() {
+                *quantity = quantity.checked_neg()?;
+            }
+        }
+        Some(self)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    pub fn policies(&self) -> impl Iterator<Item = &[u8]> {
+        self.assets.keys().map(Vec::as_slice)
+    }
+
+    pub fn tokens(&self, policy_id: &[u8]) -> Option<&BTreeMap<AssetName, i128>> {
+        self.assets.get(policy_id)
+    }
+
+    /// Every asset in the value as `(policy_id, asset_name, quantity)`, in ascending order.
+    pub fn flatten(&self) -> impl Iterator<Item = (&[u8], &[u8], i128)> {
+        self.assets.iter().flat_map(|(policy_id, assets)| {
+            assets.iter().map(|(asset_name, quantity)| {
+                (policy_id.as_slice(), asset_name.as_slice(), *quantity)
+            })
+        })
+    }
+
+    /// Decodes a value, rejecting maps whose keys are not in strictly ascending order,
+    /// zero quantities and policies without any assets. This is the form Aiken and the
+    /// ledger produce, so anything else was built by hand.
+    pub fn from_plutus_strict(data: PlutusData) -> Result<Self, DecodeError> {
+        Self::decode(data, true)
+    }
+
+    fn into_flat(self) -> impl Iterator<Item = (Vec<u8>, AssetName, i128)> {
+        self.assets.into_iter().flat_map(|(policy_id, assets)| {
+            assets
+                .into_iter()
+                .map(move |(asset_name, quantity)| (policy_id.clone(), asset_name, quantity))
+        })
+    }
+
+    // leaves the value as it was if the quantity overflows
+    fn add_quantity(
+        &mut self,
+        policy_id: Vec<u8>,
+        asset_name: AssetName,
+        quantity: i128,
+    ) -> Option<()> {
+        let total = self
+            .quantity_of(&policy_id, &asset_name)
+            .checked_add(quantity)?;
+        self.set_quantity(policy_id, asset_name, total);
+        Some(())
+    }
+
+    fn set_quantity(&mut self, policy_id: Vec<u8>, asset_name: AssetName, quantity: i128) {
+        if quantity != 0 {
+            let assets = self.assets.entry(policy_id).or_default();
+            assets.insert(asset_name, quantity);
+        } else if let Some(assets) = self.assets.get_mut(&policy_id) {
+            assets.remove(&asset_name);
+            if assets.is_empty() {
+                self.assets.remove(&policy_id);
+            }
+        }
+    }
+
+    fn decode(data: PlutusData, strict: bool) -> Result<Self, DecodeError> {
+        let mut value = Self::zero();
+        let policies = decode_keys::<Vec<u8>>(data, strict)?;
+        for (policy_index, (policy_id, assets)) in policies.into_iter().enumerate() {
+            let in_policy = |e: DecodeError| e.with_map_value(policy_index);
+            let assets = decode_keys::<AssetName>(assets, strict).map_err(in_policy)?;
+            if strict && assets.is_empty() {
+                return Err(in_policy(DecodeError::validation("policy has no assets")));
+            }
+            for (asset_index, (asset_name, quantity)) in assets.into_iter().enumerate() {
+                let in_asset = |e: DecodeError| in_policy(e.with_map_value(asset_index));
+                let quantity = i128::from_plutus(quantity).map_err(in_asset)?;
+                if strict && quantity == 0 {
+                    return Err(in_asset(DecodeError::validation("quantity is zero")));
+                }
+                let before = value.quantity_of(&policy_id, &asset_name);
+                if value
+                    .add_quantity(policy_id.clone(), asset_name.clone(), quantity)
+                    .is_none()
+                {
+                    return Err(in_asset(DecodeError::out_of_range(format!(
+                        "{before} + {quantity}"
+                    ))));
+                }
+            }
+        }
+        Ok(value)
+    }
+}
+
+impl AsPlutus for Value {
+    /// Accepts keys in any order, adding up the quantities of repeated assets and
+    /// dropping assets whose quantity is zero. Fails if the quantities of an asset
+    /// add up to more than an `i128` can hold.
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        Self::decode(data, false)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        let kvps = self
+            .assets
+            .into_iter()
+            .map(|(policy_id, assets)| (policy_id.to_plutus(), assets.to_plutus()))
+            .collect();
+        create_map(kvps)
+    }
+}
+
+impl Add for Value {
+    type Output = Option<Value>;
+
+    fn add(self, rhs: Value) -> Option<Value> {
+        self.checked_merge(rhs)
+    }
+}
+
+impl Sub for Value {
+    type Output = Option<Value>;
+
+    fn sub(self, rhs: Value) -> Option<Value> {
+        self.checked_sub(rhs)
+    }
+}
+
+impl Neg for Value {
+    type Output = Option<Value>;
+
+    fn neg(self) -> Option<Value> {
+        self.checked_negate()
+    }
+}
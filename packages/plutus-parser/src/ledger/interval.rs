@@ -0,0 +1,390 @@
+use std::{
+    cmp::Ordering,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use plutus_parser_derive::AsPlutus;
+
+use crate::{DecodeError, EncodeError, PlutusData};
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IntervalBoundType<T = i64> {
+    NegativeInfinity,
+    Finite(T),
+    PositiveInfinity,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IntervalBound<T = i64> {
+    pub bound_type: IntervalBoundType<T>,
+    pub is_inclusive: bool,
+}
+
+/// A range of values, usually POSIX times in milliseconds, with the same behavior as
+/// Aiken's `aiken/interval` module.
+///
+/// As in Aiken, integers are discrete, so an open interval between two adjacent
+/// integers such as `(1, 2)` is empty. Checking for emptiness and combining intervals
+/// needs `T` to implement `IntervalValue`.
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Interval<T = i64> {
+    pub lower_bound: IntervalBound<T>,
+    pub upper_bound: IntervalBound<T>,
+}
+
+impl<T> IntervalBound<T> {
+    fn inclusive(bound_type: IntervalBoundType<T>) -> Self {
+        Self {
+            bound_type,
+            is_inclusive: true,
+        }
+    }
+
+    fn exclusive(bound_type: IntervalBoundType<T>) -> Self {
+        Self {
+            bound_type,
+            is_inclusive: false,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> IntervalBound<U> {
+        let bound_type = match self.bound_type {
+            IntervalBoundType::NegativeInfinity => IntervalBoundType::NegativeInfinity,
+            IntervalBoundType::Finite(value) => IntervalBoundType::Finite(f(value)),
+            IntervalBoundType::PositiveInfinity => IntervalBoundType::PositiveInfinity,
+        };
+        IntervalBound {
+            bound_type,
+            is_inclusive: self.is_inclusive,
+        }
+    }
+
+    pub fn try_map<U, E>(self, f: impl FnOnce(T) -> Result<U, E>) -> Result<IntervalBound<U>, E> {
+        let bound_type = match self.bound_type {
+            IntervalBoundType::NegativeInfinity => IntervalBoundType::NegativeInfinity,
+            IntervalBoundType::Finite(value) => IntervalBoundType::Finite(f(value)?),
+            IntervalBoundType::PositiveInfinity => IntervalBoundType::PositiveInfinity,
+        };
+        Ok(IntervalBound {
+            bound_type,
+            is_inclusive: self.is_inclusive,
+        })
+    }
+}
+
+/// A value an `Interval` can range over. For integers, an exclusive bound is the same
+/// as an inclusive bound on the next integer, which is how Aiken compares bounds. Other
+/// types, like `SystemTime`, keep the default and always have values between two
+/// different values.
+pub trait IntervalValue: Ord {
+    /// Whether `upper` directly follows `lower`, with no values between them.
+    fn is_adjacent(_lower: &Self, _upper: &Self) -> bool {
+        false
+    }
+}
+
+macro_rules! impl_discrete_interval_value {
+    ($($ty:ty),*) => {
+        $(
+            impl IntervalValue for $ty {
+                fn is_adjacent(lower: &Self, upper: &Self) -> bool {
+                    lower.checked_add(1) == Some(*upper)
+                }
+            }
+        )*
+    };
+}
+
+impl_discrete_interval_value!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+impl IntervalValue for SystemTime {}
+
+impl IntervalValue for Duration {}
+
+impl<T: IntervalValue> IntervalBound<T> {
+    // an inclusive lower bound starts before an exclusive one at the same value, and at
+    // the same place as an exclusive one on the value before it
+    fn cmp_lower(&self, other: &Self) -> Ordering {
+        use IntervalBoundType::Finite;
+        let same_place = match (&self.bound_type, &other.bound_type) {
+            (Finite(a), Finite(b)) if !self.is_inclusive && other.is_inclusive => {
+                T::is_adjacent(a, b)
+            }
+            (Finite(a), Finite(b)) if self.is_inclusive && !other.is_inclusive => {
+                T::is_adjacent(b, a)
+            }
+            _ => false,
+        };
+        if same_place {
+            return Ordering::Equal;
+        }
+        self.bound_type
+            .cmp(&other.bound_type)
+            .then_with(|| other.is_inclusive.cmp(&self.is_inclusive))
+    }
+
+    // an inclusive upper bound ends after an exclusive one at the same value, and at
+    // the same place as an exclusive one on the value after it
+    fn cmp_upper(&self, other: &Self) -> Ordering {
+        use IntervalBoundType::Finite;
+        let same_place = match (&self.bound_type, &other.bound_type) {
+            (Finite(a), Finite(b)) if self.is_inclusive && !other.is_inclusive => {
+                T::is_adjacent(a, b)
+            }
+            (Finite(a), Finite(b)) if !self.is_inclusive && other.is_inclusive => {
+                T::is_adjacent(b, a)
+            }
+            _ => false,
+        };
+        if same_place {
+            return Ordering::Equal;
+        }
+        self.bound_type
+            .cmp(&other.bound_type)
+            .then_with(|| self.is_inclusive.cmp(&other.is_inclusive))
+    }
+}
+
+impl<T> Interval<T> {
+    /// The interval which contains every value.
+    pub fn everything() -> Self {
+        Self {
+            lower_bound: IntervalBound::inclusive(IntervalBoundType::NegativeInfinity),
+            upper_bound: IntervalBound::inclusive(IntervalBoundType::PositiveInfinity),
+        }
+    }
+
+    /// The interval which contains no values.
+    pub fn empty() -> Self {
+        Self {
+            lower_bound: IntervalBound::inclusive(IntervalBoundType::PositiveInfinity),
+            upper_bound: IntervalBound::inclusive(IntervalBoundType::NegativeInfinity),
+        }
+    }
+
+    /// Every value from `lower` onwards, including `lower`.
+    pub fn after(lower: T) -> Self {
+        Self {
+            lower_bound: IntervalBound::inclusive(IntervalBoundType::Finite(lower)),
+            upper_bound: IntervalBound::inclusive(IntervalBoundType::PositiveInfinity),
+        }
+    }
+
+    /// Every value after `lower`, excluding `lower`.
+    pub fn entirely_after(lower: T) -> Self {
+        Self {
+            lower_bound: IntervalBound::exclusive(IntervalBoundType::Finite(lower)),
+            upper_bound: IntervalBound::inclusive(IntervalBoundType::PositiveInfinity),
+        }
+    }
+
+    /// Every value up to `upper`, including `upper`.
+    pub fn before(upper: T) -> Self {
+        Self {
+            lower_bound: IntervalBound::inclusive(IntervalBoundType::NegativeInfinity),
+            upper_bound: IntervalBound::inclusive(IntervalBoundType::Finite(upper)),
+        }
+    }
+
+    /// Every value before `upper`, excluding `upper`.
+    pub fn entirely_before(upper: T) -> Self {
+        Self {
+            lower_bound: IntervalBound::inclusive(IntervalBoundType::NegativeInfinity),
+            upper_bound: IntervalBound::exclusive(IntervalBoundType::Finite(upper)),
+        }
+    }
+
+    /// Every value from `lower` to `upper`, including both.
+    pub fn between(lower: T, upper: T) -> Self {
+        Self {
+            lower_bound: IntervalBound::inclusive(IntervalBoundType::Finite(lower)),
+            upper_bound: IntervalBound::inclusive(IntervalBoundType::Finite(upper)),
+        }
+    }
+
+    /// Every value between `lower` and `upper`, excluding both.
+    pub fn entirely_between(lower: T, upper: T) -> Self {
+        Self {
+            lower_bound: IntervalBound::exclusive(IntervalBoundType::Finite(lower)),
+            upper_bound: IntervalBound::exclusive(IntervalBoundType::Finite(upper)),
+        }
+    }
+
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Interval<U> {
+        Interval {
+            lower_bound: self.lower_bound.map(&mut f),
+            upper_bound: self.upper_bound.map(&mut f),
+        }
+    }
+
+    pub fn try_map<U, E>(self, mut f: impl FnMut(T) -> Result<U, E>) -> Result<Interval<U>, E> {
+        Ok(Interval {
+            lower_bound: self.lower_bound.try_map(&mut f)?,
+            upper_bound: self.upper_bound.try_map(&mut f)?,
+        })
+    }
+}
+
+impl<T: Ord> Interval<T> {
+    pub fn contains(&self, value: &T) -> bool {
+        let above_lower = match &self.lower_bound.bound_type {
+            IntervalBoundType::NegativeInfinity => true,
+            IntervalBoundType::Finite(lower) if self.lower_bound.is_inclusive => lower <= value,
+            IntervalBoundType::Finite(lower) => lower < value,
+            IntervalBoundType::PositiveInfinity => false,
+        };
+        let below_upper = match &self.upper_bound.bound_type {
+            IntervalBoundType::NegativeInfinity => false,
+            IntervalBoundType::Finite(upper) if self.upper_bound.is_inclusive => value <= upper,
+            IntervalBoundType::Finite(upper) => value < upper,
+            IntervalBoundType::PositiveInfinity => true,
+        };
+        above_lower && below_upper
+    }
+
+    /// Whether every value in the interval comes after `point`.
+    pub fn is_entirely_after(&self, point: &T) -> bool {
+        match &self.lower_bound.bound_type {
+            IntervalBoundType::Finite(lower) if self.lower_bound.is_inclusive => point < lower,
+            IntervalBoundType::Finite(lower) => point <= lower,
+            _ => false,
+        }
+    }
+
+    /// Whether every value in the interval comes before `point`.
+    pub fn is_entirely_before(&self, point: &T) -> bool {
+        match &self.upper_bound.bound_type {
+            IntervalBoundType::Finite(upper) if self.upper_bound.is_inclusive => upper < point,
+            IntervalBoundType::Finite(upper) => upper <= point,
+            _ => false,
+        }
+    }
+}
+
+impl<T: IntervalValue> Interval<T> {
+    /// Whether the interval contains no values. An open interval between two adjacent
+    /// integers is empty, as in Aiken.
+    pub fn is_empty(&self) -> bool {
+        let lower = &self.lower_bound;
+        let upper = &self.upper_bound;
+        match lower.bound_type.cmp(&upper.bound_type) {
+            Ordering::Less => match (&lower.bound_type, &upper.bound_type) {
+                (IntervalBoundType::Finite(a), IntervalBoundType::Finite(b))
+                    if !lower.is_inclusive && !upper.is_inclusive =>
+                {
+                    T::is_adjacent(a, b)
+                }
+                _ => false,
+            },
+            Ordering::Equal => !(lower.is_inclusive && upper.is_inclusive),
+            Ordering::Greater => true,
+        }
+    }
+
+    /// The smallest interval which contains both intervals.
+    pub fn hull(self, other: Self) -> Self {
+        Self {
+            lower_bound: min_by(
+                self.lower_bound,
+                other.lower_bound,
+                IntervalBound::cmp_lower,
+            ),
+            upper_bound: max_by(
+                self.upper_bound,
+                other.upper_bound,
+                IntervalBound::cmp_upper,
+            ),
+        }
+    }
+
+    /// The largest interval which is contained by both intervals.
+    pub fn intersection(self, other: Self) -> Self {
+        Self {
+            lower_bound: max_by(
+                self.lower_bound,
+                other.lower_bound,
+                IntervalBound::cmp_lower,
+            ),
+            upper_bound: min_by(
+                self.upper_bound,
+                other.upper_bound,
+                IntervalBound::cmp_upper,
+            ),
+        }
+    }
+}
+
+fn min_by<T>(a: T, b: T, cmp: impl FnOnce(&T, &T) -> Ordering) -> T {
+    if cmp(&b, &a) == Ordering::Less { b } else { a }
+}
+
+fn max_by<T>(a: T, b: T, cmp: impl FnOnce(&T, &T) -> Ordering) -> T {
+    if cmp(&b, &a) == Ordering::Greater {
+        b
+    } else {
+        a
+    }
+}
+
+/// Converts a time to milliseconds since the UNIX epoch, which is how Plutus
+/// represents time. Anything below a millisecond is truncated. Fails if the time is
+/// too far from the epoch to fit in an `i64`.
+pub fn to_posix_millis(time: SystemTime) -> Result<i64, EncodeError> {
+    let millis = match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => i128::try_from(duration.as_millis()),
+        Err(error) => i128::try_from(error.duration().as_millis()).map(|millis| -millis),
+    };
+    millis
+        .ok()
+        .and_then(|millis| i64::try_from(millis).ok())
+        .ok_or_else(|| EncodeError::out_of_range(format!("{time:?}")))
+}
+
+/// Converts milliseconds since the UNIX epoch to a time. Fails if the platform's
+/// `SystemTime` can't represent it.
+pub fn from_posix_millis(millis: i64) -> Result<SystemTime, DecodeError> {
+    let duration = Duration::from_millis(millis.unsigned_abs());
+    let time = if millis < 0 {
+        UNIX_EPOCH.checked_sub(duration)
+    } else {
+        UNIX_EPOCH.checked_add(duration)
+    };
+    time.ok_or_else(|| DecodeError::out_of_range(millis))
+}
+
+impl TryFrom<Interval<SystemTime>> for Interval<i64> {
+    type Error = EncodeError;
+
+    fn try_from(interval: Interval<SystemTime>) -> Result<Self, EncodeError> {
+        interval.try_map(to_posix_millis)
+    }
+}
+
+impl TryFrom<Interval<i64>> for Interval<SystemTime> {
+    type Error = DecodeError;
+
+    fn try_from(interval: Interval<i64>) -> Result<Self, DecodeError> {
+        interval.try_map(from_posix_millis)
+    }
+}
+
+/// Times are encoded as POSIX milliseconds, so an `Interval<SystemTime>` has the same
+/// encoding as a validity range. `to_plutus` panics on times which don't fit in an
+/// `i64` of milliseconds, which `check_to_plutus` reports as an error instead. Decoding
+/// fails with an out of range error for times the platform can't represent.
+impl crate::AsPlutus for SystemTime {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        <i64 as crate::AsPlutus>::from_plutus(data).and_then(from_posix_millis)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        let millis = to_posix_millis(self).expect("time out of range");
+        crate::AsPlutus::to_plutus(millis)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        to_posix_millis(*self).map(|_| ())
+    }
+}
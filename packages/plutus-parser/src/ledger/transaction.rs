@@ -0,0 +1,40 @@
+use plutus_parser_derive::AsPlutus;
+
+use crate::{Hash, PlutusData};
+
+use super::{Address, Interval, ScriptHash, Value};
+
+pub type TransactionId = Hash<32>;
+pub type DataHash = Hash<32>;
+
+/// The validity range of a transaction, in POSIX milliseconds.
+pub type ValidityRange = Interval;
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OutputReference {
+    pub transaction_id: TransactionId,
+    pub output_index: u64,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Datum {
+    NoDatum,
+    DatumHash(DataHash),
+    InlineDatum(PlutusData),
+}
+
+pub type OutputDatum = Datum;
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Output {
+    pub address: Address,
+    pub value: Value,
+    pub datum: Datum,
+    pub reference_script: Option<ScriptHash>,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Input {
+    pub output_reference: OutputReference,
+    pub output: Output,
+}
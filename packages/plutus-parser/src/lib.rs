@@ -1,4 +1,21 @@
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+mod collections;
+#[cfg(feature = "ledger")]
+pub mod ledger;
+mod limits;
+mod plutus_tx;
 mod primitives;
+#[cfg(feature = "ledger")]
+pub mod script_context;
+mod span;
+mod stack;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+// lets code generated by the derive macro refer to this crate by name
+#[cfg(feature = "ledger")]
+extern crate self as plutus_parser;
 
 #[cfg(feature = "derive")]
 pub use plutus_parser_derive::*;
@@ -31,31 +48,155 @@ pub use pallas_v1::{
     BigInt, BoundedBytes, Constr, Hash, Int, KeyValuePairs, MaybeIndefArray, PlutusData,
 };
 
+#[cfg(feature = "proptest")]
+pub use proptest;
+
+pub use collections::{Dict, Pairs};
+pub use limits::{DecodeLimits, Limit};
+pub use plutus_tx::{AsPlutusTx, PlutusTx};
+pub use span::{Span, render_hex};
+pub use stack::grow_stack;
+
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq, Eq)]
-#[error("decode error at {path}: {kind}")]
+#[derive(Error, Debug)]
+#[error("decode error at {}: {kind}", display_path(.path))]
 pub struct DecodeError {
-    kind: DecodeErrorKind,
-    path: String,
+    // boxed to keep results small, since errors are rare
+    kind: Box<DecodeErrorKind>,
+    // the path and positions are built up from the innermost value outwards, so they
+    // are stored innermost first and reversed when they are read
+    path: Vec<PathSegment>,
+    positions: Vec<usize>,
+    span: Option<Span>,
+}
+
+// where the error was found in the input doesn't change what the error is
+impl PartialEq for DecodeError {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.path == other.path
+    }
+}
+impl Eq for DecodeError {}
+
+/// One step of the path from the top-level value to the value which failed to decode.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A struct or variant field, by name or by position.
+    Field(String),
+    /// An item in a list.
+    Index(usize),
+    /// An enum variant.
+    Variant(String),
+    /// The key of the map entry at this position.
+    MapKey(usize),
+    /// The value of the map entry at this position.
+    MapValue(usize),
+}
+
+// `path` is innermost first, as the errors store it
+fn display_path(path: &[PathSegment]) -> String {
+    let mut result = String::new();
+    for (index, segment) in path.iter().rev().enumerate() {
+        match segment {
+            PathSegment::Field(name) if index == 0 => result.push_str(name),
+            PathSegment::Field(name) => {
+                result.push('.');
+                result.push_str(name);
+            }
+            PathSegment::Index(i) => result.push_str(&format!("[{i}]")),
+            PathSegment::Variant(name) => result.push_str(&format!("::{name}")),
+            PathSegment::MapKey(i) => result.push_str(&format!("[(key #{i})]")),
+            PathSegment::MapValue(i) => result.push_str(&format!("[(value #{i})]")),
+        }
+    }
+    result
+}
+
+fn display_type_name(type_name: &Option<&'static str>) -> String {
+    match type_name {
+        Some(name) => format!(" for {name}"),
+        None => String::new(),
+    }
+}
+
+fn display_variants(type_name: &Option<&'static str>, expected: &[(u64, &str)]) -> String {
+    let mut result = display_type_name(type_name);
+    if !expected.is_empty() {
+        let variants: Vec<String> = expected
+            .iter()
+            .map(|(index, name)| format!("{index} {name}"))
+            .collect();
+        result.push_str(&format!(" (expected {})", variants.join(", ")));
+    }
+    result
 }
 
 impl DecodeError {
     pub fn new(kind: DecodeErrorKind) -> Self {
         Self {
-            kind,
-            path: String::new(),
+            kind: Box::new(kind),
+            path: vec![],
+            positions: vec![],
+            span: None,
         }
     }
 
+    pub fn kind(&self) -> &DecodeErrorKind {
+        &self.kind
+    }
+
+    /// The path to the value which failed to decode, starting from the outermost value.
+    pub fn path(&self) -> Vec<PathSegment> {
+        self.path.iter().rev().cloned().collect()
+    }
+
+    pub fn into_kind(self) -> DecodeErrorKind {
+        *self.kind
+    }
+
+    /// The position of each value on the way from the outermost value to the value
+    /// which failed to decode, counting the children of a map as `key, value, ...`.
+    pub fn positions(&self) -> Vec<usize> {
+        self.positions.iter().rev().copied().collect()
+    }
+
+    /// Where the value which failed to decode is in the input. Only errors from
+    /// `from_plutus_bytes` have a span.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// Renders a hex dump of `bytes` with the value which failed to decode highlighted,
+    /// if the error has a span. `bytes` should be the input to `from_plutus_bytes`.
+    pub fn render_hex(&self, bytes: &[u8]) -> Option<String> {
+        self.span().map(|span| render_hex(bytes, span))
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Finds the value which failed to decode in `bytes`, the CBOR it was decoded from.
+    pub fn with_span_in(mut self, bytes: &[u8]) -> Self {
+        self.span = span::locate(bytes, &self.positions());
+        self
+    }
+
     pub fn unexpected_variant(variant: u64) -> Self {
-        Self::new(DecodeErrorKind::UnexpectedVariant { variant })
+        Self::new(DecodeErrorKind::UnexpectedVariant {
+            variant,
+            type_name: None,
+            expected: &[],
+        })
     }
 
     pub fn unexpected_type<E: Into<String>, A: Into<String>>(expected: E, actual: A) -> Self {
         Self::new(DecodeErrorKind::UnexpectedType {
             expected: expected.into(),
             actual: actual.into(),
+            type_name: None,
         })
     }
 
@@ -82,31 +223,118 @@ impl DecodeError {
     }
 
     pub fn invalid_cbor(error: minicbor::decode::Error) -> Self {
-        Self::new(DecodeErrorKind::InvalidCbor(MinicborDecodeError(error)))
+        let span = error.position().map(|offset| Span { offset, len: 1 });
+        Self {
+            span,
+            ..Self::new(DecodeErrorKind::InvalidCbor(MinicborDecodeError(error)))
+        }
+    }
+
+    pub fn limit_exceeded(limit: Limit, max: usize) -> Self {
+        Self::new(DecodeErrorKind::LimitExceeded { limit, max })
+    }
+
+    pub fn duplicate_key(index: usize) -> Self {
+        Self::new(DecodeErrorKind::DuplicateKey { index })
+    }
+
+    pub fn duplicate_item(index: usize) -> Self {
+        Self::new(DecodeErrorKind::DuplicateItem { index })
     }
 
     pub fn custom(message: impl Into<String>) -> Self {
         Self::new(DecodeErrorKind::Custom(message.into()))
     }
 
-    pub fn with_field_name(mut self, name: impl std::fmt::Display) -> Self {
-        if self.path.is_empty() {
-            self.path = name.to_string();
-        } else if self.path.starts_with("[") || self.path.starts_with(":") {
-            self.path = format!("{name}{}", self.path);
-        } else {
-            self.path = format!("{name}.{}", self.path);
+    pub fn validation(message: impl std::fmt::Display) -> Self {
+        Self::new(DecodeErrorKind::Validation(message.to_string()))
+    }
+
+    /// Records that the error happened inside `segment`. Callers add segments from the
+    /// innermost value outwards, so each one is placed at the start of the path.
+    pub fn with_segment(mut self, segment: PathSegment) -> Self {
+        self.path.push(segment);
+        self
+    }
+
+    /// Records that the error happened inside a field, by name or by position.
+    pub fn with_field_name(self, name: impl std::fmt::Display) -> Self {
+        self.with_segment(PathSegment::Field(name.to_string()))
+    }
+
+    /// Records that the error happened inside the child of a constr, list or map at
+    /// `position`. Callers add positions from the innermost value outwards.
+    pub fn with_position(mut self, position: usize) -> Self {
+        self.positions.push(position);
+        self
+    }
+
+    /// Moves the outermost position along by `offset`, for errors from fields which
+    /// were decoded out of the middle of a constr, like flattened ones.
+    pub fn shift_position(mut self, offset: usize) -> Self {
+        if let Some(position) = self.positions.last_mut() {
+            *position += offset;
         }
         self
     }
+
+    /// Names the Rust type which was being decoded, if this is an unexpected type or
+    /// variant error which doesn't already name one.
+    pub fn with_type_name(mut self, name: &'static str) -> Self {
+        if let DecodeErrorKind::UnexpectedVariant { type_name, .. }
+        | DecodeErrorKind::UnexpectedType { type_name, .. } = &mut *self.kind
+        {
+            type_name.get_or_insert(name);
+        }
+        self
+    }
+
+    /// Lists the variants the type accepts, if this is an unexpected variant error.
+    pub fn with_expected_variants(mut self, variants: &'static [(u64, &'static str)]) -> Self {
+        if let DecodeErrorKind::UnexpectedVariant { expected, .. } = &mut *self.kind {
+            *expected = variants;
+        }
+        self
+    }
+
+    pub fn with_index(self, index: usize) -> Self {
+        self.with_segment(PathSegment::Index(index))
+            .with_position(index)
+    }
+
+    pub fn with_variant(self, name: impl std::fmt::Display) -> Self {
+        self.with_segment(PathSegment::Variant(name.to_string()))
+    }
+
+    pub fn with_map_key(self, index: usize) -> Self {
+        self.with_segment(PathSegment::MapKey(index))
+            .with_position(2 * index)
+    }
+
+    pub fn with_map_value(self, index: usize) -> Self {
+        self.with_segment(PathSegment::MapValue(index))
+            .with_position(2 * index + 1)
+    }
 }
 
 #[derive(Error, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum DecodeErrorKind {
-    #[error("unexpected variant {variant}")]
-    UnexpectedVariant { variant: u64 },
-    #[error("unexpected type (expected {expected}, found {actual})")]
-    UnexpectedType { expected: String, actual: String },
+    #[error("unexpected variant {variant}{}", display_variants(.type_name, .expected))]
+    UnexpectedVariant {
+        variant: u64,
+        /// The Rust type being decoded, if known.
+        type_name: Option<&'static str>,
+        /// The index and name of every variant of the type, if known.
+        expected: &'static [(u64, &'static str)],
+    },
+    #[error("unexpected type{} (expected {expected}, found {actual})", display_type_name(.type_name))]
+    UnexpectedType {
+        expected: String,
+        actual: String,
+        /// The Rust type being decoded, if known.
+        type_name: Option<&'static str>,
+    },
     #[error("unexpected length for array (expected {expected}, found {actual})")]
     WrongLength { expected: usize, actual: usize },
     #[error("unexpected field count for tuple (expected {expected}, found {actual})")]
@@ -119,10 +347,101 @@ pub enum DecodeErrorKind {
     },
     #[error("value {value} out of range")]
     OutOfRange { value: String },
+    #[error("duplicate key in map entry #{index}")]
+    DuplicateKey { index: usize },
+    #[error("duplicate item at index {index}")]
+    DuplicateItem { index: usize },
+    #[error("{limit} exceeds the limit of {max}")]
+    LimitExceeded { limit: Limit, max: usize },
     #[error("invalid cbor: {0}")]
     InvalidCbor(MinicborDecodeError),
     #[error("{0}")]
     Custom(String),
+    #[error("validation failed: {0}")]
+    Validation(String),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("encode error at {}: {kind}", display_path(.path))]
+pub struct EncodeError {
+    kind: Box<EncodeErrorKind>,
+    // innermost first, like `DecodeError::path`
+    path: Vec<PathSegment>,
+}
+
+impl EncodeError {
+    pub fn new(kind: EncodeErrorKind) -> Self {
+        Self {
+            kind: Box::new(kind),
+            path: vec![],
+        }
+    }
+
+    pub fn kind(&self) -> &EncodeErrorKind {
+        &self.kind
+    }
+
+    /// The path from the top-level value to the value which couldn't be encoded.
+    pub fn path(&self) -> Vec<PathSegment> {
+        self.path.iter().rev().cloned().collect()
+    }
+
+    pub fn into_kind(self) -> EncodeErrorKind {
+        *self.kind
+    }
+
+    pub fn out_of_range<T: ToString>(value: T) -> Self {
+        Self::new(EncodeErrorKind::OutOfRange {
+            value: value.to_string(),
+        })
+    }
+
+    pub fn custom<T: ToString>(message: T) -> Self {
+        Self::new(EncodeErrorKind::Custom(message.to_string()))
+    }
+
+    pub fn validation<T: std::fmt::Display>(message: T) -> Self {
+        Self::new(EncodeErrorKind::Validation(message.to_string()))
+    }
+
+    /// Adds a segment to the start of the path. Callers add segments from the innermost
+    /// value outwards, as the error passes through each value.
+    pub fn with_segment(mut self, segment: PathSegment) -> Self {
+        self.path.push(segment);
+        self
+    }
+
+    /// Records that the error happened inside a field, by name or by position.
+    pub fn with_field_name(self, name: impl std::fmt::Display) -> Self {
+        self.with_segment(PathSegment::Field(name.to_string()))
+    }
+
+    pub fn with_index(self, index: usize) -> Self {
+        self.with_segment(PathSegment::Index(index))
+    }
+
+    pub fn with_variant(self, name: impl std::fmt::Display) -> Self {
+        self.with_segment(PathSegment::Variant(name.to_string()))
+    }
+
+    pub fn with_map_key(self, index: usize) -> Self {
+        self.with_segment(PathSegment::MapKey(index))
+    }
+
+    pub fn with_map_value(self, index: usize) -> Self {
+        self.with_segment(PathSegment::MapValue(index))
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodeErrorKind {
+    #[error("value {value} out of range")]
+    OutOfRange { value: String },
+    #[error("{0}")]
+    Custom(String),
+    #[error("validation failed: {0}")]
+    Validation(String),
 }
 
 #[derive(Error, Debug)]
@@ -139,14 +458,63 @@ pub trait AsPlutus: Sized {
     fn from_plutus(data: PlutusData) -> Result<Self, DecodeError>;
     fn to_plutus(self) -> PlutusData;
 
+    /// Decodes CBOR-encoded plutus data. Input nested more than
+    /// `DecodeLimits::DEFAULT_MAX_DEPTH` levels deep is rejected, since decoding it could
+    /// overflow the stack.
     fn from_plutus_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let limits = DecodeLimits::max_depth(DecodeLimits::DEFAULT_MAX_DEPTH);
+        Self::from_plutus_bytes_with_limits(bytes, limits)
+    }
+
+    /// Decodes untrusted input like `from_plutus_bytes`, but rejects it without decoding
+    /// it if it goes over any of `limits`. Raising `max_depth` above the default needs a
+    /// large enough stack for the deepest input it allows.
+    fn from_plutus_bytes_with_limits(
+        bytes: &[u8],
+        limits: DecodeLimits,
+    ) -> Result<Self, DecodeError> {
+        limits.check_bytes(bytes)?;
         let data = minicbor::decode::<PlutusData>(bytes).map_err(DecodeError::invalid_cbor)?;
+        Self::from_plutus(data).map_err(|e| e.with_span_in(bytes))
+    }
+
+    /// Decodes untrusted data like `from_plutus`, but rejects it without walking it into
+    /// `Self` if it goes over any of `limits`. `max_size` is not checked.
+    fn from_plutus_with_limits(
+        data: PlutusData,
+        limits: DecodeLimits,
+    ) -> Result<Self, DecodeError> {
+        limits.check(&data)?;
         Self::from_plutus(data)
     }
 
     fn to_plutus_bytes(self) -> Vec<u8> {
-        let data = self.to_plutus();
-        minicbor::to_vec(data).expect("infallible")
+        stack::encode(self.to_plutus())
+    }
+
+    /// Checks that the value can be encoded, returning an error where it breaks one of its
+    /// invariants. `TryToPlutus::try_to_plutus` runs this before `to_plutus`.
+    ///
+    /// Derived types run their field constraints and `validate` function, and containers
+    /// check each of their values.
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+
+    /// Checks whether `data` can be decoded, like `from_plutus`, but keeps going after a
+    /// failure and returns every error it finds. Returns nothing if the data is valid.
+    ///
+    /// Derived types, tuples and the standard containers check each of their values in
+    /// turn; other types report the first error from `from_plutus`.
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        validate_by_decoding::<Self>(data)
+    }
+
+    fn vec_validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        match parse_array_ref(data) {
+            Ok(items) => validate_items::<Self>(items),
+            Err(error) => vec![error],
+        }
     }
 
     fn vec_from_plutus(data: PlutusData) -> Result<Vec<Self>, DecodeError> {
@@ -154,9 +522,7 @@ pub trait AsPlutus: Sized {
         items
             .into_iter()
             .enumerate()
-            .map(|(index, item)| {
-                Self::from_plutus(item).map_err(|e| e.with_field_name(format!("[{index}]")))
-            })
+            .map(|(index, item)| Self::from_plutus(item).map_err(|e| e.with_index(index)))
             .collect()
     }
 
@@ -172,9 +538,7 @@ pub trait AsPlutus: Sized {
         let result = items
             .into_iter()
             .enumerate()
-            .map(|(index, item)| {
-                Self::from_plutus(item).map_err(|e| e.with_field_name(format!("[{index}]")))
-            })
+            .map(|(index, item)| Self::from_plutus(item).map_err(|e| e.with_index(index)))
             .collect::<Result<Vec<Self>, DecodeError>>()?;
 
         result
@@ -187,6 +551,129 @@ pub trait AsPlutus: Sized {
     }
 }
 
+pub trait AsPlutusFields: Sized {
+    const FIELD_COUNT: usize;
+
+    fn from_plutus_fields(
+        fields: &mut impl Iterator<Item = PlutusData>,
+    ) -> Result<Self, DecodeError>;
+    fn to_plutus_fields(self, fields: &mut Vec<PlutusData>);
+
+    /// Checks the next `FIELD_COUNT` fields, like `AsPlutus::validate_plutus`.
+    fn validate_plutus_fields(fields: &mut std::slice::Iter<'_, PlutusData>) -> Vec<DecodeError> {
+        let mut values = fields.by_ref().take(Self::FIELD_COUNT).cloned();
+        match Self::from_plutus_fields(&mut values) {
+            Ok(_) => vec![],
+            Err(error) => vec![error],
+        }
+    }
+
+    /// Checks that the fields can be encoded, like `AsPlutus::check_to_plutus`.
+    fn check_to_plutus_fields(&self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+/// Fallible conversion into `PlutusData`, which refuses to encode values that break
+/// their type's invariants instead of producing data that can't be decoded again.
+/// Every `AsPlutus` type implements it, using `AsPlutus::check_to_plutus`.
+pub trait TryToPlutus: Sized {
+    fn try_to_plutus(self) -> Result<PlutusData, EncodeError>;
+
+    fn try_to_plutus_bytes(self) -> Result<Vec<u8>, EncodeError> {
+        let data = self.try_to_plutus()?;
+        Ok(stack::encode(data))
+    }
+}
+
+impl<T: AsPlutus> TryToPlutus for T {
+    fn try_to_plutus(self) -> Result<PlutusData, EncodeError> {
+        self.check_to_plutus()?;
+        Ok(self.to_plutus())
+    }
+}
+
+/// Checks every item of a list with `check`, adding its index to the error.
+pub(crate) fn check_items<'a, T: 'a>(
+    items: impl IntoIterator<Item = &'a T>,
+    check: impl Fn(&T) -> Result<(), EncodeError>,
+) -> Result<(), EncodeError> {
+    for (index, item) in items.into_iter().enumerate() {
+        check(item).map_err(|e| e.with_index(index))?;
+    }
+    Ok(())
+}
+
+/// Checks every entry of a map, adding its position to the error.
+pub(crate) fn check_entries<'a, K: 'a, V: 'a>(
+    entries: impl IntoIterator<Item = (&'a K, &'a V)>,
+    check_key: impl Fn(&K) -> Result<(), EncodeError>,
+    check_value: impl Fn(&V) -> Result<(), EncodeError>,
+) -> Result<(), EncodeError> {
+    for (index, (key, value)) in entries.into_iter().enumerate() {
+        check_key(key).map_err(|e| e.with_map_key(index))?;
+        check_value(value).map_err(|e| e.with_map_value(index))?;
+    }
+    Ok(())
+}
+
+/// Decodes a copy of `data`, returning the error if it fails. This is how types which
+/// don't check their values one at a time implement `AsPlutus::validate_plutus`.
+pub fn validate_by_decoding<T: AsPlutus>(data: &PlutusData) -> Vec<DecodeError> {
+    match T::from_plutus(data.clone()) {
+        Ok(_) => vec![],
+        Err(error) => vec![error],
+    }
+}
+
+/// Returns `errors`, or if there are none, the error from decoding the whole value.
+/// This catches problems which span several values, such as repeated map keys.
+pub(crate) fn or_decode_error<T: AsPlutus>(
+    errors: Vec<DecodeError>,
+    data: &PlutusData,
+) -> Vec<DecodeError> {
+    if errors.is_empty() {
+        validate_by_decoding::<T>(data)
+    } else {
+        errors
+    }
+}
+
+/// Checks every entry of a map, adding its position to the errors.
+pub(crate) fn validate_entries<K: AsPlutus, V: AsPlutus>(data: &PlutusData) -> Vec<DecodeError> {
+    let kvps = match parse_map_ref(data) {
+        Ok(kvps) => kvps,
+        Err(error) => return vec![error],
+    };
+    let mut errors = vec![];
+    for (index, (key, value)) in kvps.iter().enumerate() {
+        errors.extend(
+            K::validate_plutus(key)
+                .into_iter()
+                .map(|e| e.with_map_key(index)),
+        );
+        errors.extend(
+            V::validate_plutus(value)
+                .into_iter()
+                .map(|e| e.with_map_value(index)),
+        );
+    }
+    errors
+}
+
+/// Checks every item of a list, adding its index to the errors.
+pub(crate) fn validate_items<T: AsPlutus>(items: &[PlutusData]) -> Vec<DecodeError> {
+    items
+        .iter()
+        .enumerate()
+        .flat_map(|(index, item)| {
+            T::validate_plutus(item)
+                .into_iter()
+                .map(move |e| e.with_index(index))
+        })
+        .collect()
+}
+
 pub fn parse_array(data: PlutusData) -> Result<Vec<PlutusData>, DecodeError> {
     let array = match data {
         PlutusData::Array(array) => array,
@@ -197,6 +684,13 @@ pub fn parse_array(data: PlutusData) -> Result<Vec<PlutusData>, DecodeError> {
     Ok(array.to_vec())
 }
 
+pub fn parse_array_ref(data: &PlutusData) -> Result<&[PlutusData], DecodeError> {
+    match data {
+        PlutusData::Array(array) => Ok(array),
+        other => Err(DecodeError::unexpected_type("Array", type_name(other))),
+    }
+}
+
 pub fn parse_tuple<const N: usize>(data: PlutusData) -> Result<[PlutusData; N], DecodeError> {
     let array = parse_array(data)?;
     array
@@ -217,6 +711,19 @@ pub fn parse_constr(data: PlutusData) -> Result<(u64, Vec<PlutusData>), DecodeEr
     Ok((variant, constr.fields.to_vec()))
 }
 
+pub fn parse_constr_ref(data: &PlutusData) -> Result<(u64, &[PlutusData]), DecodeError> {
+    let constr = match data {
+        PlutusData::Constr(constr) => constr,
+        other => {
+            return Err(DecodeError::unexpected_type("Constr", type_name(other)));
+        }
+    };
+    let Some(variant) = constr.constructor_value() else {
+        return Err(DecodeError::custom("value has invalid tag"));
+    };
+    Ok((variant, &constr.fields))
+}
+
 pub fn parse_variant<const N: usize>(
     variant: u64,
     fields: Vec<PlutusData>,
@@ -236,6 +743,21 @@ pub fn parse_map(data: PlutusData) -> Result<Vec<(PlutusData, PlutusData)>, Deco
     Ok(kvps.to_vec())
 }
 
+pub fn parse_map_ref(data: &PlutusData) -> Result<&[(PlutusData, PlutusData)], DecodeError> {
+    match data {
+        PlutusData::Map(kvps) => Ok(kvps),
+        other => Err(DecodeError::unexpected_type("Map", type_name(other))),
+    }
+}
+
+pub fn next_field(
+    fields: &mut impl Iterator<Item = PlutusData>,
+) -> Result<PlutusData, DecodeError> {
+    fields
+        .next()
+        .ok_or_else(|| DecodeError::custom("missing field"))
+}
+
 pub fn create_constr(variant: u64, fields: Vec<PlutusData>) -> PlutusData {
     let (tag, any_constructor) = match variant {
         0..=6 => (variant + 121, None),
@@ -0,0 +1,239 @@
+use crate::minicbor::{Decoder, data::Type};
+use crate::{BigInt, DecodeError, PlutusData, Span};
+
+/// Bounds on the size and shape of untrusted input, checked before it is decoded.
+///
+/// Depth counts nested lists, maps and constructor fields, elements count the items of
+/// a single list or the entries of a single map, and the byte string length applies to
+/// each byte string (including big integers) once its chunks are joined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DecodeLimits {
+    pub max_depth: usize,
+    pub max_elements: usize,
+    pub max_bytes_len: usize,
+    /// The size of the encoded input. Only checked when decoding from bytes.
+    pub max_size: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            max_elements: 65_536,
+            max_bytes_len: 65_536,
+            max_size: 1 << 20,
+        }
+    }
+}
+
+/// The limit a value went over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Limit {
+    Depth,
+    Elements,
+    BytesLen,
+    Size,
+}
+
+impl std::fmt::Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Depth => "nesting depth",
+            Self::Elements => "number of elements",
+            Self::BytesLen => "byte string length",
+            Self::Size => "input size",
+        })
+    }
+}
+
+// a list or map which is still being read
+struct Open {
+    // the values left to read, or None if it ends with a break
+    remaining: Option<u64>,
+    values: u64,
+    max_values: u64,
+    // whether it adds a level of nesting, which the [index, fields] pair of a tag 102
+    // constr doesn't, so that it counts the same as other constrs
+    nested: bool,
+}
+
+impl DecodeLimits {
+    /// The deepest nesting `from_plutus_bytes` accepts. Decoding and dropping plutus data
+    /// recurses once per level, so this leaves room for it on small thread stacks.
+    pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+    /// Limits which only bound the nesting depth.
+    pub fn max_depth(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            max_elements: usize::MAX,
+            max_bytes_len: usize::MAX,
+            max_size: usize::MAX,
+        }
+    }
+
+    /// Checks CBOR-encoded plutus data against the limits without building it, so that
+    /// oversized input is rejected before anything is allocated for it.
+    pub fn check_bytes(&self, bytes: &[u8]) -> Result<(), DecodeError> {
+        if bytes.len() > self.max_size {
+            return Err(DecodeError::limit_exceeded(Limit::Size, self.max_size));
+        }
+        let exceeded = |limit, max, offset| {
+            DecodeError::limit_exceeded(limit, max).with_span(Span { offset, len: 1 })
+        };
+
+        let mut open: Vec<Open> = vec![];
+        let mut depth = 0;
+        let mut decoder = Decoder::new(bytes);
+        loop {
+            if let Some(parent) = open.last_mut() {
+                let finished = match parent.remaining {
+                    Some(remaining) => remaining == 0,
+                    None => decoder.datatype().map_err(DecodeError::invalid_cbor)? == Type::Break,
+                };
+                if finished {
+                    if parent.remaining.is_none() {
+                        decoder.set_position(decoder.position() + 1);
+                    }
+                    if open.pop().is_some_and(|closed| closed.nested) {
+                        depth -= 1;
+                    }
+                    if open.is_empty() {
+                        break;
+                    }
+                    continue;
+                }
+                if let Some(remaining) = &mut parent.remaining {
+                    *remaining -= 1;
+                }
+                parent.values += 1;
+                if parent.values > parent.max_values {
+                    let offset = decoder.position();
+                    return Err(exceeded(Limit::Elements, self.max_elements, offset));
+                }
+            }
+
+            let offset = decoder.position();
+            // tags only label the value which follows them
+            let mut general_constr = false;
+            while decoder.datatype().map_err(DecodeError::invalid_cbor)? == Type::Tag {
+                let tag = decoder.tag().map_err(DecodeError::invalid_cbor)?;
+                general_constr = tag.as_u64() == 102;
+            }
+            let max_elements = self.max_elements as u64;
+            let children = match decoder.datatype().map_err(DecodeError::invalid_cbor)? {
+                Type::Array | Type::ArrayIndef => {
+                    let len = decoder.array().map_err(DecodeError::invalid_cbor)?;
+                    Some((len, max_elements))
+                }
+                // maps are limited by their number of entries, not keys and values
+                Type::Map | Type::MapIndef => {
+                    let len = decoder.map().map_err(DecodeError::invalid_cbor)?;
+                    Some((
+                        len.map(|len| len.saturating_mul(2)),
+                        max_elements.saturating_mul(2),
+                    ))
+                }
+                Type::Bytes => {
+                    let len = decoder.bytes().map_err(DecodeError::invalid_cbor)?.len();
+                    if len > self.max_bytes_len {
+                        return Err(exceeded(Limit::BytesLen, self.max_bytes_len, offset));
+                    }
+                    None
+                }
+                Type::BytesIndef => {
+                    let mut len = 0;
+                    for chunk in decoder.bytes_iter().map_err(DecodeError::invalid_cbor)? {
+                        len += chunk.map_err(DecodeError::invalid_cbor)?.len();
+                        if len > self.max_bytes_len {
+                            return Err(exceeded(Limit::BytesLen, self.max_bytes_len, offset));
+                        }
+                    }
+                    None
+                }
+                _ => {
+                    decoder.skip().map_err(DecodeError::invalid_cbor)?;
+                    None
+                }
+            };
+            if let Some((remaining, max_values)) = children {
+                let nested = !general_constr;
+                if nested {
+                    if depth >= self.max_depth {
+                        return Err(exceeded(Limit::Depth, self.max_depth, offset));
+                    }
+                    depth += 1;
+                }
+                if remaining.is_some_and(|remaining| remaining > max_values) {
+                    return Err(exceeded(Limit::Elements, self.max_elements, offset));
+                }
+                open.push(Open {
+                    remaining,
+                    values: 0,
+                    max_values,
+                    nested,
+                });
+            }
+            if open.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks decoded plutus data against the limits, except for `max_size`.
+    pub fn check(&self, data: &PlutusData) -> Result<(), DecodeError> {
+        let mut pending = vec![(data, 0)];
+        while let Some((data, depth)) = pending.pop() {
+            let elements = match data {
+                PlutusData::Constr(constr) => constr.fields.len(),
+                PlutusData::Array(items) => items.len(),
+                PlutusData::Map(kvps) => kvps.len(),
+                PlutusData::BoundedBytes(bytes) => {
+                    self.check_bytes_len(bytes.len())?;
+                    continue;
+                }
+                PlutusData::BigInt(BigInt::BigUInt(bytes) | BigInt::BigNInt(bytes)) => {
+                    self.check_bytes_len(bytes.len())?;
+                    continue;
+                }
+                PlutusData::BigInt(BigInt::Int(_)) => continue,
+            };
+            if depth >= self.max_depth {
+                return Err(DecodeError::limit_exceeded(Limit::Depth, self.max_depth));
+            }
+            if elements > self.max_elements {
+                return Err(DecodeError::limit_exceeded(
+                    Limit::Elements,
+                    self.max_elements,
+                ));
+            }
+            match data {
+                PlutusData::Constr(constr) => {
+                    pending.extend(constr.fields.iter().map(|field| (field, depth + 1)));
+                }
+                PlutusData::Array(items) => {
+                    pending.extend(items.iter().map(|item| (item, depth + 1)));
+                }
+                PlutusData::Map(kvps) => {
+                    for (key, value) in kvps.iter() {
+                        pending.push((key, depth + 1));
+                        pending.push((value, depth + 1));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn check_bytes_len(&self, len: usize) -> Result<(), DecodeError> {
+        if len > self.max_bytes_len {
+            return Err(DecodeError::limit_exceeded(
+                Limit::BytesLen,
+                self.max_bytes_len,
+            ));
+        }
+        Ok(())
+    }
+}
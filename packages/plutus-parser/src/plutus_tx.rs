@@ -0,0 +1,397 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::{
+    AsPlutus, BigInt, BoundedBytes, Constr, DecodeError, Dict, EncodeError, Hash, KeyValuePairs,
+    MaybeIndefArray, Pairs, PlutusData, check_entries, check_items, create_array, create_constr,
+    parse_array, parse_array_ref, parse_constr, parse_constr_ref, parse_variant,
+    validate_by_decoding, validate_items,
+};
+
+/// Conversion to and from `PlutusData` following the conventions of PlutusTx's
+/// `makeIsData`, rather than Aiken's. The only difference for built-in types is that
+/// tuples are encoded as `Constr 0 [a, b, ...]` instead of arrays; everything else
+/// (including every derived type) is encoded the same way under both conventions.
+pub trait AsPlutusTx: Sized {
+    fn from_plutus_tx(data: PlutusData) -> Result<Self, DecodeError>;
+    fn to_plutus_tx(self) -> PlutusData;
+
+    fn vec_from_plutus_tx(data: PlutusData) -> Result<Vec<Self>, DecodeError> {
+        let items = parse_array(data)?;
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| Self::from_plutus_tx(item).map_err(|e| e.with_index(index)))
+            .collect()
+    }
+
+    fn vec_to_plutus_tx(value: Vec<Self>) -> PlutusData {
+        create_array(value.into_iter().map(Self::to_plutus_tx).collect())
+    }
+
+    /// Checks whether `data` can be decoded, like `AsPlutus::validate_plutus`.
+    fn validate_plutus_tx(data: &PlutusData) -> Vec<DecodeError> {
+        validate_by_decoding::<PlutusTx<Self>>(data)
+    }
+
+    fn vec_validate_plutus_tx(data: &PlutusData) -> Vec<DecodeError> {
+        match parse_array_ref(data) {
+            Ok(items) => validate_items::<PlutusTx<Self>>(items),
+            Err(error) => vec![error],
+        }
+    }
+
+    /// Checks that the value can be encoded, like `AsPlutus::check_to_plutus`.
+    fn check_to_plutus_tx(&self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+/// Wraps a value to encode it with the PlutusTx conventions wherever an `AsPlutus` type
+/// is expected, such as `PlutusTx<(u64, u64)>` for a Haskell tuple.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PlutusTx<T>(pub T);
+
+impl<T: AsPlutusTx> AsPlutus for PlutusTx<T> {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        T::from_plutus_tx(data).map(PlutusTx)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        self.0.to_plutus_tx()
+    }
+
+    fn vec_from_plutus(data: PlutusData) -> Result<Vec<Self>, DecodeError> {
+        let items = T::vec_from_plutus_tx(data)?;
+        Ok(items.into_iter().map(PlutusTx).collect())
+    }
+
+    fn vec_to_plutus(value: Vec<Self>) -> PlutusData {
+        T::vec_to_plutus_tx(value.into_iter().map(|item| item.0).collect())
+    }
+
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        T::validate_plutus_tx(data)
+    }
+
+    fn vec_validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        T::vec_validate_plutus_tx(data)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        self.0.check_to_plutus_tx()
+    }
+
+    fn array_from_plutus<const N: usize>(data: PlutusData) -> Result<[Self; N], DecodeError> {
+        let items = Self::vec_from_plutus(data)?;
+        items
+            .try_into()
+            .map_err(|e: Vec<_>| DecodeError::wrong_length(N, e.len()))
+    }
+
+    fn array_to_plutus<const N: usize>(value: [Self; N]) -> PlutusData {
+        Self::vec_to_plutus(value.into())
+    }
+}
+
+// types which are encoded the same way under both conventions
+macro_rules! impl_same_as_aiken {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl AsPlutusTx for $ty {
+                fn from_plutus_tx(data: PlutusData) -> Result<Self, DecodeError> {
+                    AsPlutus::from_plutus(data)
+                }
+
+                fn to_plutus_tx(self) -> PlutusData {
+                    AsPlutus::to_plutus(self)
+                }
+
+                fn validate_plutus_tx(data: &PlutusData) -> Vec<DecodeError> {
+                    <Self as AsPlutus>::validate_plutus(data)
+                }
+
+                fn check_to_plutus_tx(&self) -> Result<(), EncodeError> {
+                    AsPlutus::check_to_plutus(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_same_as_aiken!(
+    PlutusData,
+    Constr<PlutusData>,
+    KeyValuePairs<PlutusData, PlutusData>,
+    MaybeIndefArray<PlutusData>,
+    BigInt,
+    BoundedBytes,
+    bool,
+    u16,
+    u32,
+    u64,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    (),
+    String,
+    std::num::NonZeroU8,
+    std::num::NonZeroU16,
+    std::num::NonZeroU32,
+    std::num::NonZeroU64,
+    std::num::NonZeroI8,
+    std::num::NonZeroI16,
+    std::num::NonZeroI32,
+    std::num::NonZeroI64,
+);
+
+#[cfg(feature = "bytes")]
+impl_same_as_aiken!(bytes::Bytes);
+
+#[cfg(feature = "ledger")]
+impl_same_as_aiken!(
+    crate::ledger::Value,
+    crate::script_context::v1::MintValue,
+    std::time::SystemTime,
+);
+
+impl AsPlutusTx for u8 {
+    fn from_plutus_tx(data: PlutusData) -> Result<Self, DecodeError> {
+        AsPlutus::from_plutus(data)
+    }
+
+    fn to_plutus_tx(self) -> PlutusData {
+        AsPlutus::to_plutus(self)
+    }
+
+    // Vec<u8> is a ByteString in PlutusTx too
+    fn vec_from_plutus_tx(data: PlutusData) -> Result<Vec<Self>, DecodeError> {
+        Self::vec_from_plutus(data)
+    }
+
+    fn vec_to_plutus_tx(value: Vec<Self>) -> PlutusData {
+        Self::vec_to_plutus(value)
+    }
+
+    fn validate_plutus_tx(data: &PlutusData) -> Vec<DecodeError> {
+        <Self as AsPlutus>::validate_plutus(data)
+    }
+
+    fn vec_validate_plutus_tx(data: &PlutusData) -> Vec<DecodeError> {
+        Self::vec_validate_plutus(data)
+    }
+}
+
+impl<const BYTES: usize> AsPlutusTx for Hash<BYTES> {
+    fn from_plutus_tx(data: PlutusData) -> Result<Self, DecodeError> {
+        AsPlutus::from_plutus(data)
+    }
+
+    fn to_plutus_tx(self) -> PlutusData {
+        AsPlutus::to_plutus(self)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($param:ident $index:expr),*) => {
+        impl<$($param),*> AsPlutusTx for ($($param),*)
+        where
+            $($param: AsPlutusTx),*
+        {
+            #[allow(non_snake_case)]
+            fn from_plutus_tx(data: PlutusData) -> Result<Self, DecodeError> {
+                let (variant, fields) = parse_constr(data)?;
+                if variant != 0 {
+                    return Err(DecodeError::unexpected_variant(variant));
+                }
+                let [$($param),*] = parse_variant(variant, fields)?;
+                Ok(($(AsPlutusTx::from_plutus_tx($param).map_err(|e| e.with_field_name($index).with_position($index))?),*))
+            }
+
+            #[allow(non_snake_case)]
+            fn to_plutus_tx(self) -> PlutusData {
+                let ($($param),*) = self;
+                create_constr(0, vec![$($param.to_plutus_tx()),*])
+            }
+
+            #[allow(non_snake_case)]
+            fn validate_plutus_tx(data: &PlutusData) -> Vec<DecodeError> {
+                let fields = match parse_constr_ref(data) {
+                    Ok((0, fields)) => fields,
+                    Ok((variant, _)) => return vec![DecodeError::unexpected_variant(variant)],
+                    Err(error) => return vec![error],
+                };
+                let [$($param),*] = fields else {
+                    let expected = [$($index),*].len();
+                    return vec![DecodeError::wrong_variant_field_count(0, expected, fields.len())];
+                };
+                let mut errors = vec![];
+                $(
+                    errors.extend(
+                        <$param as AsPlutusTx>::validate_plutus_tx($param)
+                            .into_iter()
+                            .map(|e| e.with_field_name($index).with_position($index)),
+                    );
+                )*
+                errors
+            }
+
+            #[allow(non_snake_case)]
+            fn check_to_plutus_tx(&self) -> Result<(), EncodeError> {
+                let ($($param),*) = self;
+                $($param.check_to_plutus_tx().map_err(|e| e.with_field_name($index))?;)*
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_tuple!(T1 0, T2 1);
+impl_tuple!(T1 0, T2 1, T3 2);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9, T11 10);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9, T11 10, T12 11);
+
+// containers are encoded like their Aiken counterparts, but with PlutusTx contents
+macro_rules! impl_container {
+    ($([$($generics:tt)*] $ty:ty => $wrapped:ty, $unwrap:expr, $wrap:expr, $check:expr;)*) => {
+        $(
+            impl<$($generics)*> AsPlutusTx for $ty {
+                fn from_plutus_tx(data: PlutusData) -> Result<Self, DecodeError> {
+                    <$wrapped as AsPlutus>::from_plutus(data).map($unwrap)
+                }
+
+                fn to_plutus_tx(self) -> PlutusData {
+                    let wrapped: $wrapped = $wrap(self);
+                    wrapped.to_plutus()
+                }
+
+                fn validate_plutus_tx(data: &PlutusData) -> Vec<DecodeError> {
+                    <$wrapped as AsPlutus>::validate_plutus(data)
+                }
+
+                fn check_to_plutus_tx(&self) -> Result<(), EncodeError> {
+                    $check(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_container! {
+    [T: AsPlutusTx] Option<T> => Option<PlutusTx<T>>,
+        |value| value.map(|v| v.0),
+        |value: Self| value.map(PlutusTx),
+        |value: &Self| value.as_ref().map_or(Ok(()), T::check_to_plutus_tx);
+    [T: AsPlutusTx, const N: usize] [T; N] => [PlutusTx<T>; N],
+        |value: [PlutusTx<T>; N]| value.map(|v| v.0),
+        |value: Self| value.map(PlutusTx),
+        |value: &Self| check_items(value, T::check_to_plutus_tx);
+    [T: AsPlutusTx] Vec<T> => Vec<PlutusTx<T>>,
+        |value: Vec<_>| value.into_iter().map(|v| v.0).collect(),
+        |value: Self| value.into_iter().map(PlutusTx).collect(),
+        |value: &Self| check_items(value, T::check_to_plutus_tx);
+    [T: AsPlutusTx] VecDeque<T> => Vec<PlutusTx<T>>,
+        |value: Vec<_>| value.into_iter().map(|v| v.0).collect(),
+        |value: Self| value.into_iter().map(PlutusTx).collect(),
+        |value: &Self| check_items(value, T::check_to_plutus_tx);
+    [T: AsPlutusTx] Box<T> => PlutusTx<T>,
+        |value: PlutusTx<T>| Box::new(value.0),
+        |value: Self| PlutusTx(*value),
+        |value: &Self| (**value).check_to_plutus_tx();
+    [T: AsPlutusTx + Clone] std::rc::Rc<T> => PlutusTx<T>,
+        |value: PlutusTx<T>| std::rc::Rc::new(value.0),
+        |value: Self| PlutusTx(std::rc::Rc::unwrap_or_clone(value)),
+        |value: &Self| (**value).check_to_plutus_tx();
+    [T: AsPlutusTx + Clone] std::sync::Arc<T> => PlutusTx<T>,
+        |value: PlutusTx<T>| std::sync::Arc::new(value.0),
+        |value: Self| PlutusTx(std::sync::Arc::unwrap_or_clone(value)),
+        |value: &Self| (**value).check_to_plutus_tx();
+    [T: AsPlutusTx + Ord] BTreeSet<T> => BTreeSet<PlutusTx<T>>,
+        |value: BTreeSet<_>| value.into_iter().map(|v| v.0).collect(),
+        |value: Self| value.into_iter().map(PlutusTx).collect(),
+        |value: &Self| check_items(value, T::check_to_plutus_tx);
+    [T: AsPlutusTx + std::hash::Hash + Eq] HashSet<T> => HashSet<PlutusTx<T>>,
+        |value: HashSet<_>| value.into_iter().map(|v| v.0).collect(),
+        |value: Self| value.into_iter().map(PlutusTx).collect(),
+        |value: &Self| value.iter().try_for_each(T::check_to_plutus_tx);
+    [K: AsPlutusTx + Ord, V: AsPlutusTx] BTreeMap<K, V> => BTreeMap<PlutusTx<K>, PlutusTx<V>>,
+        |value: BTreeMap<_, _>| value.into_iter().map(|(k, v)| (k.0, v.0)).collect(),
+        |value: Self| value.into_iter().map(|(k, v)| (PlutusTx(k), PlutusTx(v))).collect(),
+        |value: &Self| check_entries(value, K::check_to_plutus_tx, V::check_to_plutus_tx);
+    [K: AsPlutusTx + std::hash::Hash + Eq, V: AsPlutusTx] HashMap<K, V> => HashMap<PlutusTx<K>, PlutusTx<V>>,
+        |value: HashMap<_, _>| value.into_iter().map(|(k, v)| (k.0, v.0)).collect(),
+        |value: Self| value.into_iter().map(|(k, v)| (PlutusTx(k), PlutusTx(v))).collect(),
+        |value: &Self| value.iter().try_for_each(|(k, v)| {
+            k.check_to_plutus_tx()?;
+            v.check_to_plutus_tx()
+        });
+    [K: AsPlutusTx + std::hash::Hash + Eq, V: AsPlutusTx] indexmap::IndexMap<K, V> => indexmap::IndexMap<PlutusTx<K>, PlutusTx<V>>,
+        |value: indexmap::IndexMap<_, _>| value.into_iter().map(|(k, v)| (k.0, v.0)).collect(),
+        |value: Self| value.into_iter().map(|(k, v)| (PlutusTx(k), PlutusTx(v))).collect(),
+        |value: &Self| check_entries(value, K::check_to_plutus_tx, V::check_to_plutus_tx);
+    [K: AsPlutusTx + Ord, V: AsPlutusTx] Dict<K, V> => Dict<PlutusTx<K>, PlutusTx<V>>,
+        |value: Dict<_, _>| value.into_iter().map(|(k, v)| (k.0, v.0)).collect(),
+        |value: Self| value.into_iter().map(|(k, v)| (PlutusTx(k), PlutusTx(v))).collect(),
+        |value: &Self| check_entries(value.iter(), K::check_to_plutus_tx, V::check_to_plutus_tx);
+    [K: AsPlutusTx, V: AsPlutusTx] Pairs<K, V> => Pairs<PlutusTx<K>, PlutusTx<V>>,
+        |value: Pairs<_, _>| value.into_iter().map(|(k, v)| (k.0, v.0)).collect(),
+        |value: Self| value.into_iter().map(|(k, v)| (PlutusTx(k), PlutusTx(v))).collect(),
+        |value: &Self| check_entries(value.iter().map(|(k, v)| (k, v)), K::check_to_plutus_tx, V::check_to_plutus_tx);
+}
+
+impl<T: ToOwned + ?Sized> AsPlutusTx for std::borrow::Cow<'_, T>
+where
+    T::Owned: AsPlutusTx,
+{
+    fn from_plutus_tx(data: PlutusData) -> Result<Self, DecodeError> {
+        T::Owned::from_plutus_tx(data).map(std::borrow::Cow::Owned)
+    }
+
+    fn to_plutus_tx(self) -> PlutusData {
+        self.into_owned().to_plutus_tx()
+    }
+
+    fn check_to_plutus_tx(&self) -> Result<(), EncodeError> {
+        match self {
+            std::borrow::Cow::Borrowed(value) => (*value).to_owned().check_to_plutus_tx(),
+            std::borrow::Cow::Owned(value) => value.check_to_plutus_tx(),
+        }
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array> AsPlutusTx for smallvec::SmallVec<A>
+where
+    A::Item: AsPlutusTx,
+{
+    fn from_plutus_tx(data: PlutusData) -> Result<Self, DecodeError> {
+        A::Item::vec_from_plutus_tx(data).map(Self::from_vec)
+    }
+
+    fn to_plutus_tx(self) -> PlutusData {
+        A::Item::vec_to_plutus_tx(self.into_vec())
+    }
+
+    fn check_to_plutus_tx(&self) -> Result<(), EncodeError> {
+        check_items(self, A::Item::check_to_plutus_tx)
+    }
+}
+
+#[cfg(feature = "either")]
+impl_container! {
+    [L: AsPlutusTx, R: AsPlutusTx] either::Either<L, R> => either::Either<PlutusTx<L>, PlutusTx<R>>,
+        |value: either::Either<PlutusTx<L>, PlutusTx<R>>| value.map_either(|l| l.0, |r| r.0),
+        |value: Self| value.map_either(PlutusTx, PlutusTx),
+        |value: &Self| match value {
+            either::Either::Left(l) => l.check_to_plutus_tx().map_err(|e| e.with_field_name(0).with_variant("Left")),
+            either::Either::Right(r) => r.check_to_plutus_tx().map_err(|e| e.with_field_name(0).with_variant("Right")),
+        };
+}
@@ -1,7 +1,8 @@
 use crate::{
-    AsPlutus, BigInt, BoundedBytes, Constr, DecodeError, Hash, KeyValuePairs, MaybeIndefArray,
-    PlutusData, create_array, create_constr, create_map, parse_constr, parse_map, parse_tuple,
-    parse_variant, type_name,
+    AsPlutus, BigInt, BoundedBytes, Constr, DecodeError, EncodeError, Hash, KeyValuePairs,
+    MaybeIndefArray, PlutusData, check_entries, check_items, create_array, create_constr,
+    create_map, minicbor, or_decode_error, parse_array_ref, parse_constr, parse_constr_ref,
+    parse_map, parse_tuple, parse_variant, type_name, validate_by_decoding, validate_entries,
 };
 
 impl AsPlutus for PlutusData {
@@ -127,8 +128,9 @@ macro_rules! impl_number {
         }
 
         fn to_plutus(self) -> PlutusData {
+            // every integer type up to 64 bits fits in an Int
             let val = self as i128;
-            PlutusData::BigInt(BigInt::Int(val.try_into().unwrap()))
+            PlutusData::BigInt(BigInt::Int(val.try_into().expect("integer fits in an Int")))
         }
     };
 }
@@ -142,6 +144,10 @@ impl AsPlutus for u8 {
         Ok(bytes.into())
     }
 
+    fn vec_validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        validate_by_decoding::<Vec<u8>>(data)
+    }
+
     fn vec_to_plutus(value: Vec<Self>) -> PlutusData {
         let bytes = BoundedBytes::from(value);
         PlutusData::BoundedBytes(bytes)
@@ -184,6 +190,60 @@ impl AsPlutus for i64 {
     impl_number!();
 }
 
+// an i128 can be bigger than an Int, so it also has to read and write big integers
+impl AsPlutus for i128 {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        let PlutusData::BigInt(value) = data else {
+            return Err(DecodeError::unexpected_type("BigInt", type_name(&data)));
+        };
+        let out_of_range = |prefix: &str, bytes: &[u8]| {
+            DecodeError::out_of_range(format!("{prefix}0x{}", hex::encode(bytes)))
+        };
+        match value {
+            BigInt::Int(value) => Ok(value.into()),
+            BigInt::BigUInt(bytes) => big_magnitude(&bytes)
+                .and_then(|magnitude| i128::try_from(magnitude).ok())
+                .ok_or_else(|| out_of_range("", &bytes)),
+            BigInt::BigNInt(bytes) => big_magnitude(&bytes)
+                .and_then(|magnitude| i128::try_from(magnitude).ok())
+                .map(|magnitude| -1 - magnitude)
+                .ok_or_else(|| out_of_range("-1 - ", &bytes)),
+        }
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        if let Ok(value) = self.try_into() {
+            return PlutusData::BigInt(BigInt::Int(value));
+        }
+        // a negative big integer holds -1 - n
+        let (magnitude, negative) = if self < 0 {
+            ((-1 - self) as u128, true)
+        } else {
+            (self as u128, false)
+        };
+        let bytes = magnitude.to_be_bytes();
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+        let bytes = BoundedBytes::from(bytes[start..].to_vec());
+        if negative {
+            PlutusData::BigInt(BigInt::BigNInt(bytes))
+        } else {
+            PlutusData::BigInt(BigInt::BigUInt(bytes))
+        }
+    }
+}
+
+// the value of a big-endian big integer, if it fits in a u128
+fn big_magnitude(bytes: &[u8]) -> Option<u128> {
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    let bytes = &bytes[start..];
+    if bytes.len() > 16 {
+        return None;
+    }
+    let mut buf = [0; 16];
+    buf[16 - bytes.len()..].copy_from_slice(bytes);
+    Some(u128::from_be_bytes(buf))
+}
+
 macro_rules! impl_tuple {
     ($($param:ident $index:expr),*) => {
         impl<$($param),*> AsPlutus for ($($param),*)
@@ -193,7 +253,7 @@ macro_rules! impl_tuple {
             #[allow(non_snake_case)]
             fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
                 let [$($param),*] = parse_tuple(data)?;
-                Ok(($(AsPlutus::from_plutus($param).map_err(|e| e.with_field_name($index))?),*))
+                Ok(($(AsPlutus::from_plutus($param).map_err(|e| e.with_field_name($index).with_position($index))?),*))
             }
 
             #[allow(non_snake_case)]
@@ -201,6 +261,34 @@ macro_rules! impl_tuple {
                 let ($($param),*) = self;
                 create_array(vec![$($param.to_plutus()),*])
             }
+
+            #[allow(non_snake_case)]
+            fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+                let items = match parse_array_ref(data) {
+                    Ok(items) => items,
+                    Err(error) => return vec![error],
+                };
+                let [$($param),*] = items else {
+                    let expected = [$($index),*].len();
+                    return vec![DecodeError::wrong_tuple_field_count(expected, items.len())];
+                };
+                let mut errors = vec![];
+                $(
+                    errors.extend(
+                        <$param as AsPlutus>::validate_plutus($param)
+                            .into_iter()
+                            .map(|e| e.with_field_name($index).with_position($index)),
+                    );
+                )*
+                errors
+            }
+
+            #[allow(non_snake_case)]
+            fn check_to_plutus(&self) -> Result<(), EncodeError> {
+                let ($($param),*) = self;
+                $($param.check_to_plutus().map_err(|e| e.with_field_name($index))?;)*
+                Ok(())
+            }
         }
     };
 }
@@ -212,6 +300,54 @@ impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4);
 impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5);
 impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6);
 impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9, T11 10);
+impl_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9, T11 10, T12 11);
+
+// Aiken's Void
+impl AsPlutus for () {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        let (variant, fields) = parse_constr(data)?;
+        if variant != 0 {
+            return Err(DecodeError::unexpected_variant(variant));
+        }
+        let [] = parse_variant(variant, fields)?;
+        Ok(())
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        create_constr(0, vec![])
+    }
+}
+
+macro_rules! impl_non_zero {
+    ($($ty:ty),*) => {
+        $(
+            impl AsPlutus for $ty {
+                fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+                    let value = AsPlutus::from_plutus(data)?;
+                    Self::new(value).ok_or_else(|| DecodeError::out_of_range(value))
+                }
+
+                fn to_plutus(self) -> PlutusData {
+                    self.get().to_plutus()
+                }
+            }
+        )*
+    };
+}
+
+impl_non_zero!(
+    std::num::NonZeroU8,
+    std::num::NonZeroU16,
+    std::num::NonZeroU32,
+    std::num::NonZeroU64,
+    std::num::NonZeroI8,
+    std::num::NonZeroI16,
+    std::num::NonZeroI32,
+    std::num::NonZeroI64
+);
 
 impl AsPlutus for String {
     fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
@@ -243,7 +379,7 @@ impl<T: AsPlutus> AsPlutus for Option<T> {
         let (variant, fields) = parse_constr(data)?;
         if variant == 0 {
             let [value] = parse_variant(variant, fields)?;
-            return Ok(Some(T::from_plutus(value)?));
+            return Ok(Some(T::from_plutus(value).map_err(|e| e.with_position(0))?));
         }
         if variant == 1 {
             let [] = parse_variant(variant, fields)?;
@@ -258,6 +394,33 @@ impl<T: AsPlutus> AsPlutus for Option<T> {
             None => create_constr(1, vec![]),
         }
     }
+
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        match parse_constr_ref(data) {
+            Ok((0, [value])) => T::validate_plutus(value)
+                .into_iter()
+                .map(|e| e.with_position(0))
+                .collect(),
+            Ok((1, [])) => vec![],
+            Ok((variant @ (0 | 1), fields)) => {
+                let expected = if variant == 0 { 1 } else { 0 };
+                vec![DecodeError::wrong_variant_field_count(
+                    variant,
+                    expected,
+                    fields.len(),
+                )]
+            }
+            Ok((variant, _)) => vec![DecodeError::unexpected_variant(variant)],
+            Err(error) => vec![error],
+        }
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        match self {
+            Some(value) => value.check_to_plutus(),
+            None => Ok(()),
+        }
+    }
 }
 
 impl<T: AsPlutus, const N: usize> AsPlutus for [T; N] {
@@ -268,6 +431,15 @@ impl<T: AsPlutus, const N: usize> AsPlutus for [T; N] {
     fn to_plutus(self) -> PlutusData {
         T::array_to_plutus(self)
     }
+
+    // the length is checked by decoding once every item is valid
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        or_decode_error::<Self>(T::vec_validate_plutus(data), data)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        check_items(self, T::check_to_plutus)
+    }
 }
 
 impl<T: AsPlutus> AsPlutus for Vec<T> {
@@ -278,6 +450,240 @@ impl<T: AsPlutus> AsPlutus for Vec<T> {
     fn to_plutus(self) -> PlutusData {
         T::vec_to_plutus(self)
     }
+
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        T::vec_validate_plutus(data)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        check_items(self, T::check_to_plutus)
+    }
+}
+
+impl<T: AsPlutus> AsPlutus for Box<T> {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        T::from_plutus(data).map(Box::new)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        (*self).to_plutus()
+    }
+
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        T::validate_plutus(data)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        (**self).check_to_plutus()
+    }
+}
+
+impl<T: AsPlutus + Clone> AsPlutus for std::rc::Rc<T> {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        T::from_plutus(data).map(std::rc::Rc::new)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        std::rc::Rc::unwrap_or_clone(self).to_plutus()
+    }
+
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        T::validate_plutus(data)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        (**self).check_to_plutus()
+    }
+}
+
+impl<T: AsPlutus + Clone> AsPlutus for std::sync::Arc<T> {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        T::from_plutus(data).map(std::sync::Arc::new)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        std::sync::Arc::unwrap_or_clone(self).to_plutus()
+    }
+
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        T::validate_plutus(data)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        (**self).check_to_plutus()
+    }
+}
+
+impl<T: ToOwned + ?Sized> AsPlutus for std::borrow::Cow<'_, T>
+where
+    T::Owned: AsPlutus,
+{
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        T::Owned::from_plutus(data).map(std::borrow::Cow::Owned)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        self.into_owned().to_plutus()
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        match self {
+            std::borrow::Cow::Borrowed(value) => (*value).to_owned().check_to_plutus(),
+            std::borrow::Cow::Owned(value) => value.check_to_plutus(),
+        }
+    }
+}
+
+impl<T: AsPlutus> AsPlutus for std::collections::VecDeque<T> {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        T::vec_from_plutus(data).map(Self::from)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        T::vec_to_plutus(self.into())
+    }
+
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        T::vec_validate_plutus(data)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        check_items(self, T::check_to_plutus)
+    }
+}
+
+// sets are lists with no repeated items, and a BTreeSet must also be in ascending order
+impl<T: AsPlutus + Ord> AsPlutus for std::collections::BTreeSet<T> {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        let mut set = Self::new();
+        for (index, item) in T::vec_from_plutus(data)?.into_iter().enumerate() {
+            if let Some(last) = set.last() {
+                if *last == item {
+                    return Err(DecodeError::duplicate_item(index).with_index(index));
+                }
+                if *last > item {
+                    return Err(DecodeError::validation("items are not in ascending order")
+                        .with_index(index));
+                }
+            }
+            set.insert(item);
+        }
+        Ok(set)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        T::vec_to_plutus(self.into_iter().collect())
+    }
+
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        or_decode_error::<Self>(T::vec_validate_plutus(data), data)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        check_items(self, T::check_to_plutus)
+    }
+}
+
+impl<T: AsPlutus + std::hash::Hash + Eq> AsPlutus for std::collections::HashSet<T> {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        let mut set = Self::new();
+        for (index, item) in T::vec_from_plutus(data)?.into_iter().enumerate() {
+            if !set.insert(item) {
+                return Err(DecodeError::duplicate_item(index).with_index(index));
+            }
+        }
+        Ok(set)
+    }
+
+    // iteration order is random, so sort by the encoded items to keep the output stable
+    fn to_plutus(self) -> PlutusData {
+        let mut items: Vec<(Vec<u8>, PlutusData)> = self
+            .into_iter()
+            .map(|item| {
+                let item = item.to_plutus();
+                let bytes = minicbor::to_vec(&item).expect("infallible");
+                (bytes, item)
+            })
+            .collect();
+        items.sort_by(|a, b| a.0.cmp(&b.0));
+        create_array(items.into_iter().map(|(_, item)| item).collect())
+    }
+
+    fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+        or_decode_error::<Self>(T::vec_validate_plutus(data), data)
+    }
+
+    // items are only sorted while encoding, so errors can't say where the item will be
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        self.iter().try_for_each(T::check_to_plutus)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl AsPlutus for bytes::Bytes {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        Vec::<u8>::from_plutus(data).map(Self::from)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        self.to_vec().to_plutus()
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array> AsPlutus for smallvec::SmallVec<A>
+where
+    A::Item: AsPlutus,
+{
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        A::Item::vec_from_plutus(data).map(Self::from_vec)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        A::Item::vec_to_plutus(self.into_vec())
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        check_items(self, A::Item::check_to_plutus)
+    }
+}
+
+#[cfg(feature = "either")]
+impl<L: AsPlutus, R: AsPlutus> AsPlutus for either::Either<L, R> {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        let (variant, fields) = parse_constr(data)?;
+        if variant == 0 {
+            let [value] = parse_variant(variant, fields)?;
+            return L::from_plutus(value)
+                .map(Self::Left)
+                .map_err(|e| e.with_field_name(0).with_variant("Left").with_position(0));
+        }
+        if variant == 1 {
+            let [value] = parse_variant(variant, fields)?;
+            return R::from_plutus(value)
+                .map(Self::Right)
+                .map_err(|e| e.with_field_name(0).with_variant("Right").with_position(0));
+        }
+        Err(DecodeError::unexpected_variant(variant))
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        match self {
+            Self::Left(value) => create_constr(0, vec![value.to_plutus()]),
+            Self::Right(value) => create_constr(1, vec![value.to_plutus()]),
+        }
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        match self {
+            Self::Left(value) => value
+                .check_to_plutus()
+                .map_err(|e| e.with_field_name(0).with_variant("Left")),
+            Self::Right(value) => value
+                .check_to_plutus()
+                .map_err(|e| e.with_field_name(0).with_variant("Right")),
+        }
+    }
 }
 
 macro_rules! impl_map {
@@ -285,21 +691,18 @@ macro_rules! impl_map {
         fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
             let mut map = Self::new();
             for (index, (key, value)) in parse_map(data)?.into_iter().enumerate() {
-                let key = TKey::from_plutus(key)
-                    .map_err(|e| e.with_field_name(format!("[(key #{index})]")))?;
-                let value = TVal::from_plutus(value)
-                    .map_err(|e| e.with_field_name(format!("[(value #{index})]")))?;
-                map.insert(key, value);
+                let key = TKey::from_plutus(key).map_err(|e| e.with_map_key(index))?;
+                let value = TVal::from_plutus(value).map_err(|e| e.with_map_value(index))?;
+                if map.insert(key, value).is_some() {
+                    return Err(DecodeError::duplicate_key(index).with_map_key(index));
+                }
             }
             Ok(map)
         }
 
-        fn to_plutus(self) -> PlutusData {
-            let kvps = self
-                .into_iter()
-                .map(|(k, v)| (k.to_plutus(), v.to_plutus()))
-                .collect();
-            create_map(kvps)
+        // repeated keys are found by decoding once every entry is valid
+        fn validate_plutus(data: &PlutusData) -> Vec<DecodeError> {
+            or_decode_error::<Self>(validate_entries::<TKey, TVal>(data), data)
         }
     };
 }
@@ -308,16 +711,63 @@ impl<TKey: AsPlutus + std::hash::Hash + Eq, TVal: AsPlutus> AsPlutus
     for indexmap::IndexMap<TKey, TVal>
 {
     impl_map!();
+
+    fn to_plutus(self) -> PlutusData {
+        let kvps = self
+            .into_iter()
+            .map(|(k, v)| (k.to_plutus(), v.to_plutus()))
+            .collect();
+        create_map(kvps)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        check_entries(self, TKey::check_to_plutus, TVal::check_to_plutus)
+    }
 }
 
 impl<TKey: AsPlutus + std::hash::Hash + Eq, TVal: AsPlutus> AsPlutus
     for std::collections::HashMap<TKey, TVal>
 {
     impl_map!();
+
+    // iteration order is random, so sort by the encoded keys to keep the output stable
+    fn to_plutus(self) -> PlutusData {
+        let mut entries: Vec<(Vec<u8>, PlutusData, PlutusData)> = self
+            .into_iter()
+            .map(|(k, v)| {
+                let key = k.to_plutus();
+                let bytes = minicbor::to_vec(&key).expect("infallible");
+                (bytes, key, v.to_plutus())
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        create_map(entries.into_iter().map(|(_, k, v)| (k, v)).collect())
+    }
+
+    // entries are only sorted while encoding, so errors can't say where the entry will be
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        for (key, value) in self {
+            key.check_to_plutus()?;
+            value.check_to_plutus()?;
+        }
+        Ok(())
+    }
 }
 
 impl<TKey: AsPlutus + PartialOrd + Ord, TVal: AsPlutus> AsPlutus
     for std::collections::BTreeMap<TKey, TVal>
 {
     impl_map!();
+
+    fn to_plutus(self) -> PlutusData {
+        let kvps = self
+            .into_iter()
+            .map(|(k, v)| (k.to_plutus(), v.to_plutus()))
+            .collect();
+        create_map(kvps)
+    }
+
+    fn check_to_plutus(&self) -> Result<(), EncodeError> {
+        check_entries(self, TKey::check_to_plutus, TVal::check_to_plutus)
+    }
 }
@@ -0,0 +1,7 @@
+//! The script context passed to validators by each version of Plutus. Types which
+//! are encoded the same way as their Aiken counterparts reuse the types from
+//! [`crate::ledger`].
+
+pub mod v1;
+pub mod v2;
+pub mod v3;
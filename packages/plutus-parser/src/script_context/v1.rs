@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use plutus_parser_derive::AsPlutus;
+
+use crate::{
+    DecodeError, Hash, PlutusData,
+    ledger::{Address, AssetName, Interval, StakeCredential, Value},
+};
+
+pub type PubKeyHash = Hash<28>;
+pub type DatumHash = Hash<32>;
+pub type Datum = PlutusData;
+pub type Redeemer = PlutusData;
+pub type StakingCredential = StakeCredential;
+pub type POSIXTimeRange = Interval;
+
+/// The policy id of a token, which is empty for ada.
+pub type CurrencySymbol = Vec<u8>;
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TxId(pub Hash<32>);
+
+/// The `mint` field of a V1 or V2 transaction. The ledger always gives it an entry for
+/// zero ada, which a `Value` would drop, so this keeps every quantity as it was decoded,
+/// zero or not. Converting from a `Value` adds the zero ada entry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MintValue(pub BTreeMap<CurrencySymbol, BTreeMap<AssetName, i128>>);
+
+impl MintValue {
+    /// The minted assets, without the zero quantities.
+    pub fn to_value(&self) -> Value {
+        let mut value = Value::zero();
+        for (policy_id, assets) in &self.0 {
+            for (asset_name, quantity) in assets {
+                value = value.with_asset(policy_id.clone(), asset_name.clone(), *quantity);
+            }
+        }
+        value
+    }
+}
+
+impl From<Value> for MintValue {
+    fn from(value: Value) -> Self {
+        let mut assets = BTreeMap::from([(vec![], BTreeMap::from([(vec![], 0)]))]);
+        for (policy_id, asset_name, quantity) in value.flatten() {
+            assets
+                .entry(policy_id.to_vec())
+                .or_insert_with(BTreeMap::new)
+                .insert(asset_name.to_vec(), quantity);
+        }
+        Self(assets)
+    }
+}
+
+impl crate::AsPlutus for MintValue {
+    fn from_plutus(data: PlutusData) -> Result<Self, DecodeError> {
+        crate::AsPlutus::from_plutus(data).map(Self)
+    }
+
+    fn to_plutus(self) -> PlutusData {
+        crate::AsPlutus::to_plutus(self.0)
+    }
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TxOutRef {
+    pub id: TxId,
+    pub index: u64,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TxOut {
+    pub address: Address,
+    pub value: Value,
+    pub datum_hash: Option<DatumHash>,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TxInInfo {
+    pub out_ref: TxOutRef,
+    pub resolved: TxOut,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DCert {
+    DelegRegKey(StakingCredential),
+    DelegDeRegKey(StakingCredential),
+    DelegDelegate {
+        delegator: StakingCredential,
+        delegatee: PubKeyHash,
+    },
+    PoolRegister {
+        pool_id: PubKeyHash,
+        pool_vrf: PubKeyHash,
+    },
+    PoolRetire {
+        pool_id: PubKeyHash,
+        epoch: u64,
+    },
+    Genesis,
+    Mir,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScriptPurpose {
+    Minting(CurrencySymbol),
+    Spending(TxOutRef),
+    Rewarding(StakingCredential),
+    Certifying(DCert),
+}
+
+// the withdrawals and data are lists of Haskell tuples
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+#[plutus(convention = "plutustx")]
+pub struct TxInfo {
+    pub inputs: Vec<TxInInfo>,
+    pub outputs: Vec<TxOut>,
+    pub fee: Value,
+    pub mint: MintValue,
+    pub dcert: Vec<DCert>,
+    pub withdrawals: Vec<(StakingCredential, i64)>,
+    pub valid_range: POSIXTimeRange,
+    pub signatories: Vec<PubKeyHash>,
+    pub data: Vec<(DatumHash, Datum)>,
+    pub id: TxId,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+pub struct ScriptContext {
+    pub tx_info: TxInfo,
+    pub purpose: ScriptPurpose,
+}
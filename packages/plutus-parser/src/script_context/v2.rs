@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+
+use plutus_parser_derive::AsPlutus;
+
+use crate::ledger::{Output, Value};
+
+pub use super::v1::{
+    CurrencySymbol, DCert, Datum, DatumHash, MintValue, POSIXTimeRange, PubKeyHash, Redeemer,
+    ScriptPurpose, StakingCredential, TxId, TxOutRef,
+};
+
+pub type OutputDatum = crate::ledger::Datum;
+pub type TxOut = Output;
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TxInInfo {
+    pub out_ref: TxOutRef,
+    pub resolved: TxOut,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+pub struct TxInfo {
+    pub inputs: Vec<TxInInfo>,
+    pub reference_inputs: Vec<TxInInfo>,
+    pub outputs: Vec<TxOut>,
+    pub fee: Value,
+    pub mint: MintValue,
+    pub dcert: Vec<DCert>,
+    pub withdrawals: BTreeMap<StakingCredential, i64>,
+    pub valid_range: POSIXTimeRange,
+    pub signatories: Vec<PubKeyHash>,
+    pub redeemers: BTreeMap<ScriptPurpose, Redeemer>,
+    pub data: BTreeMap<DatumHash, Datum>,
+    pub id: TxId,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+pub struct ScriptContext {
+    pub tx_info: TxInfo,
+    pub purpose: ScriptPurpose,
+}
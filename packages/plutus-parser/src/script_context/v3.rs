@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+
+use plutus_parser_derive::AsPlutus;
+
+use crate::{
+    Hash, PlutusData,
+    ledger::{Credential, Input, OutputReference, ScriptHash, TransactionId, Value},
+};
+
+pub use super::v1::{CurrencySymbol, Datum, DatumHash, POSIXTimeRange, PubKeyHash, Redeemer};
+pub use super::v2::{OutputDatum, TxOut};
+
+/// Unlike earlier versions, V3 encodes the transaction id as bare bytes.
+pub type TxId = TransactionId;
+pub type TxOutRef = OutputReference;
+pub type TxInInfo = Input;
+pub type Lovelace = i64;
+pub type DRepCredential = Credential;
+pub type ColdCommitteeCredential = Credential;
+pub type HotCommitteeCredential = Credential;
+pub type ChangedParameters = PlutusData;
+pub type PoolId = Hash<28>;
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DRep {
+    DRep(DRepCredential),
+    AlwaysAbstain,
+    AlwaysNoConfidence,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Delegatee {
+    Stake(PubKeyHash),
+    Vote(DRep),
+    StakeVote(PubKeyHash, DRep),
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TxCert {
+    RegStaking(Credential, Option<Lovelace>),
+    UnRegStaking(Credential, Option<Lovelace>),
+    DelegStaking(Credential, Delegatee),
+    RegDeleg(Credential, Delegatee, Lovelace),
+    RegDRep(DRepCredential, Lovelace),
+    UpdateDRep(DRepCredential),
+    UnRegDRep(DRepCredential, Lovelace),
+    PoolRegister {
+        pool_id: PoolId,
+        pool_vrf: PubKeyHash,
+    },
+    PoolRetire {
+        pool_id: PoolId,
+        epoch: u64,
+    },
+    AuthHotCommittee(ColdCommitteeCredential, HotCommitteeCredential),
+    ResignColdCommittee(ColdCommitteeCredential),
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Voter {
+    Committee(HotCommitteeCredential),
+    DRep(DRepCredential),
+    StakePool(PubKeyHash),
+}
+
+#[derive(AsPlutus, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Vote {
+    No,
+    Yes,
+    Abstain,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GovernanceActionId {
+    pub tx_id: TxId,
+    pub index: u64,
+}
+
+#[derive(AsPlutus, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Rational {
+    pub numerator: i64,
+    pub denominator: i64,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProtocolVersion {
+    pub major: u64,
+    pub minor: u64,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Constitution {
+    pub script: Option<ScriptHash>,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GovernanceAction {
+    ParameterChange {
+        previous: Option<GovernanceActionId>,
+        parameters: ChangedParameters,
+        guardrail: Option<ScriptHash>,
+    },
+    HardForkInitiation {
+        previous: Option<GovernanceActionId>,
+        version: ProtocolVersion,
+    },
+    TreasuryWithdrawals {
+        withdrawals: BTreeMap<Credential, Lovelace>,
+        guardrail: Option<ScriptHash>,
+    },
+    NoConfidence {
+        previous: Option<GovernanceActionId>,
+    },
+    UpdateCommittee {
+        previous: Option<GovernanceActionId>,
+        removed: Vec<ColdCommitteeCredential>,
+        added: BTreeMap<ColdCommitteeCredential, u64>,
+        quorum: Rational,
+    },
+    NewConstitution {
+        previous: Option<GovernanceActionId>,
+        constitution: Constitution,
+    },
+    Info,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProposalProcedure {
+    pub deposit: Lovelace,
+    pub return_credential: Credential,
+    pub governance_action: GovernanceAction,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScriptPurpose {
+    Minting(CurrencySymbol),
+    Spending(TxOutRef),
+    Rewarding(Credential),
+    Certifying {
+        index: u64,
+        certificate: TxCert,
+    },
+    Voting(Voter),
+    Proposing {
+        index: u64,
+        procedure: ProposalProcedure,
+    },
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScriptInfo {
+    Minting(CurrencySymbol),
+    Spending {
+        out_ref: TxOutRef,
+        datum: Option<Datum>,
+    },
+    Rewarding(Credential),
+    Certifying {
+        index: u64,
+        certificate: TxCert,
+    },
+    Voting(Voter),
+    Proposing {
+        index: u64,
+        procedure: ProposalProcedure,
+    },
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+pub struct TxInfo {
+    pub inputs: Vec<TxInInfo>,
+    pub reference_inputs: Vec<TxInInfo>,
+    pub outputs: Vec<TxOut>,
+    pub fee: Lovelace,
+    pub mint: Value,
+    pub certificates: Vec<TxCert>,
+    pub withdrawals: BTreeMap<Credential, Lovelace>,
+    pub valid_range: POSIXTimeRange,
+    pub signatories: Vec<PubKeyHash>,
+    pub redeemers: BTreeMap<ScriptPurpose, Redeemer>,
+    pub data: BTreeMap<DatumHash, Datum>,
+    pub id: TxId,
+    pub votes: BTreeMap<Voter, BTreeMap<GovernanceActionId, Vote>>,
+    pub proposal_procedures: Vec<ProposalProcedure>,
+    pub current_treasury_amount: Option<Lovelace>,
+    pub treasury_donation: Option<Lovelace>,
+}
+
+#[derive(AsPlutus, Clone, Debug, PartialEq, Eq)]
+pub struct ScriptContext {
+    pub tx_info: TxInfo,
+    pub redeemer: Redeemer,
+    pub script_info: ScriptInfo,
+}
@@ -0,0 +1,97 @@
+use std::fmt::Write;
+
+use crate::minicbor::{Decoder, data::Type};
+
+/// The location of an encoded value in a CBOR buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl Span {
+    pub fn end(&self) -> usize {
+        self.offset + self.len
+    }
+}
+
+/// Finds the value reached by following `positions` down from the value at the start
+/// of `bytes`. Each position picks a child: a field of a constr, an item of a list,
+/// or a key (`2 * i`) or value (`2 * i + 1`) of a map.
+pub(crate) fn locate(bytes: &[u8], positions: &[usize]) -> Option<Span> {
+    let mut decoder = Decoder::new(bytes);
+    for &position in positions {
+        enter(&mut decoder)?;
+        for _ in 0..position {
+            decoder.skip().ok()?;
+        }
+    }
+    let offset = decoder.position();
+    decoder.skip().ok()?;
+    Some(Span {
+        offset,
+        len: decoder.position() - offset,
+    })
+}
+
+// moves the decoder from the start of a value to the start of its first child
+fn enter(decoder: &mut Decoder) -> Option<()> {
+    match decoder.datatype().ok()? {
+        Type::Tag => {
+            let tag: u64 = decoder.tag().ok()?.as_u64();
+            if tag == 102 {
+                // general constructors are encoded as [index, [fields]]
+                decoder.array().ok()?;
+                decoder.skip().ok()?;
+            }
+            decoder.array().ok()?;
+        }
+        Type::Array | Type::ArrayIndef => {
+            decoder.array().ok()?;
+        }
+        Type::Map | Type::MapIndef => {
+            decoder.map().ok()?;
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+const BYTES_PER_LINE: usize = 16;
+const MAX_HIGHLIGHTED_LINES: usize = 8;
+
+/// Renders a hex dump of the lines of `bytes` around `span`, with the bytes in the
+/// span marked by `^^` underneath. Long spans only show their first and last lines.
+pub fn render_hex(bytes: &[u8], span: Span) -> String {
+    let first = span.offset / BYTES_PER_LINE;
+    let last = span.end().saturating_sub(1).max(span.offset) / BYTES_PER_LINE;
+    let start = first.saturating_sub(1);
+    let end = (last + 1).min(bytes.len().saturating_sub(1) / BYTES_PER_LINE);
+
+    let mut output = String::new();
+    let mut line = start;
+    while line <= end {
+        if last - first >= MAX_HIGHLIGHTED_LINES && line == first + MAX_HIGHLIGHTED_LINES / 2 {
+            output.push_str("...\n");
+            line = last + 1 - MAX_HIGHLIGHTED_LINES / 2;
+            continue;
+        }
+        let offset = line * BYTES_PER_LINE;
+        let chunk = &bytes[offset.min(bytes.len())..(offset + BYTES_PER_LINE).min(bytes.len())];
+        let mut hex = format!("{offset:08x} ");
+        let mut marks = " ".repeat(9);
+        for (i, byte) in chunk.iter().enumerate() {
+            let highlighted = (span.offset..span.end()).contains(&(offset + i));
+            write!(hex, " {byte:02x}").expect("infallible");
+            marks.push_str(if highlighted { " ^^" } else { "   " });
+        }
+        output.push_str(&hex);
+        output.push('\n');
+        if !marks.trim().is_empty() {
+            output.push_str(marks.trim_end());
+            output.push('\n');
+        }
+        line += 1;
+    }
+    output
+}
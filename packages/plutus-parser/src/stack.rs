@@ -0,0 +1,80 @@
+use crate::{PlutusData, minicbor};
+
+// how much stack has to be left before handling another value, and how much to add
+// when there isn't
+#[cfg(feature = "stacker")]
+const RED_ZONE: usize = 128 * 1024;
+#[cfg(feature = "stacker")]
+const SEGMENT_SIZE: usize = 1024 * 1024;
+
+// pallas encodes and drops plutus data recursively, so deeper data needs a bigger stack.
+// One level takes up to 4KiB in a debug build, so this leaves room to spare. Only the
+// pages which are used are ever touched.
+#[cfg(feature = "stacker")]
+const STACK_PER_LEVEL: usize = 8 * 1024;
+
+// any thread's stack has room for this many levels
+#[cfg(feature = "stacker")]
+const SHALLOW_DEPTH: usize = 128;
+
+/// Runs `f`, moving to a new stack segment first if the current one is nearly full.
+///
+/// Derived `AsPlutus` impls call this for every value they convert, so recursive types
+/// can be encoded, decoded and checked however deeply they are nested. Hand-written
+/// impls for recursive types should do the same. Without the `stacker` feature, this
+/// just calls `f`.
+pub fn grow_stack<R>(f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "stacker")]
+    {
+        stacker::maybe_grow(RED_ZONE, SEGMENT_SIZE, f)
+    }
+    #[cfg(not(feature = "stacker"))]
+    {
+        f()
+    }
+}
+
+/// Encodes `data` to CBOR and drops it, on a stack big enough for how deeply it is
+/// nested.
+#[cfg(feature = "stacker")]
+pub(crate) fn encode(data: PlutusData) -> Vec<u8> {
+    let depth = depth(&data);
+    let encode = move || minicbor::to_vec(data).expect("infallible");
+    if depth <= SHALLOW_DEPTH {
+        encode()
+    } else {
+        stacker::grow(depth * STACK_PER_LEVEL, encode)
+    }
+}
+
+/// Encodes `data` to CBOR on the current stack.
+#[cfg(not(feature = "stacker"))]
+pub(crate) fn encode(data: PlutusData) -> Vec<u8> {
+    minicbor::to_vec(data).expect("infallible")
+}
+
+// how many constrs, lists and maps the deepest value is inside of, without recursing
+#[cfg(feature = "stacker")]
+fn depth(data: &PlutusData) -> usize {
+    let mut deepest = 0;
+    let mut pending = vec![(data, 0)];
+    while let Some((data, depth)) = pending.pop() {
+        deepest = deepest.max(depth);
+        match data {
+            PlutusData::Constr(constr) => {
+                pending.extend(constr.fields.iter().map(|field| (field, depth + 1)));
+            }
+            PlutusData::Array(items) => {
+                pending.extend(items.iter().map(|item| (item, depth + 1)));
+            }
+            PlutusData::Map(kvps) => {
+                for (key, value) in kvps.iter() {
+                    pending.push((key, depth + 1));
+                    pending.push((value, depth + 1));
+                }
+            }
+            PlutusData::BigInt(_) | PlutusData::BoundedBytes(_) => {}
+        }
+    }
+    deepest
+}
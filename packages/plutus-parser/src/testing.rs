@@ -0,0 +1,15 @@
+//! Helpers for testing how types are encoded.
+//!
+//! `assert_roundtrip` and `assert_encodes_to_hex` check single values, `Golden` keeps
+//! the encodings of values in files so changes to them show up in review, and
+//! `check_mutations` makes sure types reject malformed versions of their encoding.
+
+mod assertions;
+mod diagnostic;
+mod golden;
+mod mutations;
+
+pub use assertions::*;
+pub use diagnostic::*;
+pub use golden::*;
+pub use mutations::*;
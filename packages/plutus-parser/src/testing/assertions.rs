@@ -0,0 +1,63 @@
+use std::fmt::Debug;
+
+use super::diagnostic;
+use crate::{AsPlutus, PlutusData};
+
+/// Checks that `value` decodes back to itself after encoding it, both to plutus data and
+/// to CBOR.
+#[track_caller]
+pub fn assert_roundtrip<T: AsPlutus + Clone + Debug + PartialEq>(value: &T) {
+    let data = value.clone().to_plutus();
+    match T::from_plutus(data.clone()) {
+        Ok(decoded) => assert_eq!(&decoded, value, "decoded a different value from {data:?}"),
+        Err(error) => panic!("failed to decode {data:?}: {error}"),
+    }
+    let bytes = value.clone().to_plutus_bytes();
+    match T::from_plutus_bytes(&bytes) {
+        Ok(decoded) => assert_eq!(
+            &decoded,
+            value,
+            "decoded a different value from {}",
+            hex::encode(&bytes)
+        ),
+        Err(error) => panic!("failed to decode {}: {error}", hex::encode(&bytes)),
+    }
+}
+
+/// Checks that `value` is encoded as `data`, and that `data` decodes to `value`.
+#[track_caller]
+pub fn assert_encodes_to<T: AsPlutus + Debug + PartialEq>(value: T, data: PlutusData) {
+    match T::from_plutus(data.clone()) {
+        Ok(decoded) => assert_eq!(decoded, value, "decoded a different value from {data:?}"),
+        Err(error) => panic!("failed to decode {data:?}: {error}"),
+    }
+    assert_eq!(value.to_plutus(), data);
+}
+
+/// Checks that `value` is encoded as the CBOR in `hex`, and that the CBOR decodes to
+/// `value`. Mismatched encodings are shown in diagnostic notation as well as hex.
+#[track_caller]
+pub fn assert_encodes_to_hex<T: AsPlutus + Debug + PartialEq>(value: T, hex: &str) {
+    let expected = match hex::decode(hex.trim()) {
+        Ok(bytes) => bytes,
+        Err(error) => panic!("invalid hex {hex:?}: {error}"),
+    };
+    match T::from_plutus_bytes(&expected) {
+        Ok(decoded) => assert_eq!(decoded, value, "decoded a different value from {hex}"),
+        Err(error) => panic!("failed to decode {hex}: {error}"),
+    }
+    let actual = value.to_plutus_bytes();
+    if actual != expected {
+        panic!(
+            "unexpected encoding\n  expected: {}\n     found: {}\n\nexpected:\n{}\n\nfound:\n{}",
+            hex::encode(&expected),
+            hex::encode(&actual),
+            render(&expected),
+            render(&actual),
+        );
+    }
+}
+
+pub(super) fn render(bytes: &[u8]) -> String {
+    diagnostic(bytes).unwrap_or_else(|error| format!("<invalid cbor: {error}>"))
+}
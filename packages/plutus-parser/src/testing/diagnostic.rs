@@ -0,0 +1,187 @@
+use std::fmt::Write;
+
+use crate::minicbor::{self, Decoder, data::Type};
+
+/// Renders CBOR in the diagnostic notation of RFC 8949, like `121([_ 1, h'aa'])`.
+/// Lists and maps which contain other lists or maps put each of their items on its own
+/// line, so that changes to nested values show up clearly in a diff.
+pub fn diagnostic(bytes: &[u8]) -> Result<String, minicbor::decode::Error> {
+    let item = read(&mut Decoder::new(bytes))?;
+    let mut out = String::new();
+    item.render(&mut out, 0);
+    Ok(out)
+}
+
+enum Item {
+    Atom(String),
+    Tagged(u64, Box<Item>),
+    Entry(Box<Item>, Box<Item>),
+    Container {
+        map: bool,
+        indefinite: bool,
+        items: Vec<Item>,
+    },
+}
+
+impl Item {
+    fn is_container(&self) -> bool {
+        match self {
+            Self::Atom(_) => false,
+            Self::Tagged(_, item) => item.is_container(),
+            Self::Entry(key, value) => key.is_container() || value.is_container(),
+            Self::Container { .. } => true,
+        }
+    }
+
+    fn render(&self, out: &mut String, indent: usize) {
+        match self {
+            Self::Atom(atom) => out.push_str(atom),
+            Self::Tagged(tag, item) => {
+                write!(out, "{tag}(").unwrap();
+                item.render(out, indent);
+                out.push(')');
+            }
+            Self::Entry(key, value) => {
+                key.render(out, indent);
+                out.push_str(": ");
+                value.render(out, indent);
+            }
+            Self::Container {
+                map,
+                indefinite,
+                items,
+            } => {
+                let (open, close) = if *map { ('{', '}') } else { ('[', ']') };
+                out.push(open);
+                if *indefinite {
+                    out.push('_');
+                }
+                if items.iter().any(Item::is_container) {
+                    for (i, item) in items.iter().enumerate() {
+                        if i > 0 {
+                            out.push(',');
+                        }
+                        out.push('\n');
+                        push_indent(out, indent + 1);
+                        item.render(out, indent + 1);
+                    }
+                    out.push('\n');
+                    push_indent(out, indent);
+                } else {
+                    if *indefinite {
+                        out.push(' ');
+                    }
+                    for (i, item) in items.iter().enumerate() {
+                        if i > 0 {
+                            out.push_str(", ");
+                        }
+                        item.render(out, indent);
+                    }
+                }
+                out.push(close);
+            }
+        }
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    out.extend(std::iter::repeat_n("  ", indent));
+}
+
+fn read(decoder: &mut Decoder) -> Result<Item, minicbor::decode::Error> {
+    let item = match decoder.datatype()? {
+        Type::U8
+        | Type::U16
+        | Type::U32
+        | Type::U64
+        | Type::I8
+        | Type::I16
+        | Type::I32
+        | Type::I64
+        | Type::Int => Item::Atom(i128::from(decoder.int()?).to_string()),
+        Type::Bytes => Item::Atom(bytes(decoder.bytes()?)),
+        Type::BytesIndef => {
+            let chunks = decoder
+                .bytes_iter()?
+                .map(|chunk| chunk.map(bytes))
+                .collect::<Result<Vec<_>, _>>()?;
+            Item::Atom(format!("(_ {})", chunks.join(", ")))
+        }
+        Type::String => Item::Atom(format!("{:?}", decoder.str()?)),
+        Type::StringIndef => {
+            let chunks = decoder
+                .str_iter()?
+                .map(|chunk| chunk.map(|chunk| format!("{chunk:?}")))
+                .collect::<Result<Vec<_>, _>>()?;
+            Item::Atom(format!("(_ {})", chunks.join(", ")))
+        }
+        Type::Array | Type::ArrayIndef => {
+            let len = decoder.array()?;
+            let items = read_items(decoder, len, read)?;
+            Item::Container {
+                map: false,
+                indefinite: len.is_none(),
+                items,
+            }
+        }
+        Type::Map | Type::MapIndef => {
+            let len = decoder.map()?;
+            let items = read_items(decoder, len, |decoder| {
+                let key = read(decoder)?;
+                let value = read(decoder)?;
+                Ok(Item::Entry(Box::new(key), Box::new(value)))
+            })?;
+            Item::Container {
+                map: true,
+                indefinite: len.is_none(),
+                items,
+            }
+        }
+        Type::Tag => {
+            let tag = decoder.tag()?.as_u64();
+            Item::Tagged(tag, Box::new(read(decoder)?))
+        }
+        Type::Bool => Item::Atom(decoder.bool()?.to_string()),
+        Type::Null => {
+            decoder.null()?;
+            Item::Atom("null".into())
+        }
+        Type::Undefined => {
+            decoder.undefined()?;
+            Item::Atom("undefined".into())
+        }
+        Type::Simple => Item::Atom(format!("simple({})", decoder.simple()?)),
+        // floats never appear in plutus data
+        other => {
+            decoder.skip()?;
+            Item::Atom(format!("<{other}>"))
+        }
+    };
+    Ok(item)
+}
+
+fn read_items(
+    decoder: &mut Decoder,
+    len: Option<u64>,
+    mut read_item: impl FnMut(&mut Decoder) -> Result<Item, minicbor::decode::Error>,
+) -> Result<Vec<Item>, minicbor::decode::Error> {
+    let mut items = vec![];
+    match len {
+        Some(len) => {
+            for _ in 0..len {
+                items.push(read_item(decoder)?);
+            }
+        }
+        None => {
+            while decoder.datatype()? != Type::Break {
+                items.push(read_item(decoder)?);
+            }
+            decoder.set_position(decoder.position() + 1);
+        }
+    }
+    Ok(items)
+}
+
+fn bytes(bytes: &[u8]) -> String {
+    format!("h'{}'", hex::encode(bytes))
+}
@@ -0,0 +1,123 @@
+use std::fmt::{Debug, Write};
+use std::fs;
+use std::path::PathBuf;
+
+use super::assertions::render;
+use crate::AsPlutus;
+
+/// A directory of golden files, each holding the encoding of one value, so that changes
+/// to encodings are caught in tests and show up in review.
+///
+/// Each file holds the CBOR as hex on its first line, followed by the same CBOR in
+/// diagnostic notation for people reading it. Only the hex is compared.
+#[derive(Clone, Debug)]
+pub struct Golden {
+    dir: PathBuf,
+}
+
+impl Golden {
+    /// The environment variable which makes `check` write encodings instead of comparing
+    /// them, when it is set to anything but `0`.
+    pub const UPDATE_VAR: &'static str = "UPDATE_GOLDEN";
+
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The file which holds the encoding called `name`.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.golden"))
+    }
+
+    /// Checks that `value` is encoded exactly as in the golden file called `name`, and
+    /// that it decodes back to itself. Fails with a diff of the two encodings if they are
+    /// different, or if the file doesn't exist.
+    ///
+    /// With `UPDATE_GOLDEN=1` in the environment, writes the encoding to the file instead.
+    #[track_caller]
+    pub fn check<T: AsPlutus + Clone + Debug + PartialEq>(&self, name: &str, value: &T) {
+        if std::env::var(Self::UPDATE_VAR).is_ok_and(|update| update != "0") {
+            self.update(name, value);
+            return;
+        }
+        let actual = value.clone().to_plutus_bytes();
+        let path = self.path(name);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) => panic!(
+                "can't read golden file {}: {error}\nrun with {}=1 to create it",
+                path.display(),
+                Self::UPDATE_VAR,
+            ),
+        };
+        let expected_hex = contents.lines().next().unwrap_or_default().trim();
+        let expected = match hex::decode(expected_hex) {
+            Ok(bytes) => bytes,
+            Err(error) => panic!("golden file {} is invalid: {error}", path.display()),
+        };
+        if actual != expected {
+            panic!(
+                "encoding of {name} doesn't match {}\n\n- {}\n+ {}\n\n{}\nrun with {}=1 to accept the new encoding",
+                path.display(),
+                expected_hex,
+                hex::encode(&actual),
+                diff(&render(&expected), &render(&actual)),
+                Self::UPDATE_VAR,
+            );
+        }
+        match T::from_plutus_bytes(&actual) {
+            Ok(decoded) => assert_eq!(&decoded, value, "{name} decoded to a different value"),
+            Err(error) => panic!("failed to decode {name}: {error}"),
+        }
+    }
+
+    /// Writes the encoding of `value` to the golden file called `name`, creating the
+    /// directory if needed.
+    pub fn update<T: AsPlutus + Clone>(&self, name: &str, value: &T) {
+        let bytes = value.clone().to_plutus_bytes();
+        let contents = format!("{}\n\n{}\n", hex::encode(&bytes), render(&bytes));
+        let path = self.path(name);
+        if fs::read_to_string(&path).is_ok_and(|existing| existing == contents) {
+            return;
+        }
+        if let Err(error) = fs::create_dir_all(&self.dir) {
+            panic!("can't create {}: {error}", self.dir.display());
+        }
+        if let Err(error) = fs::write(&path, contents) {
+            panic!("can't write golden file {}: {error}", path.display());
+        }
+    }
+}
+
+// a line by line diff, marking removed lines with - and added lines with +
+fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+    // the length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            writeln!(out, "  {}", old[i]).unwrap();
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            writeln!(out, "- {}", old[i]).unwrap();
+            i += 1;
+        } else {
+            writeln!(out, "+ {}", new[j]).unwrap();
+            j += 1;
+        }
+    }
+    out
+}
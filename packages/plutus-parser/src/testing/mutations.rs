@@ -0,0 +1,333 @@
+use std::fmt;
+
+use crate::{
+    AsPlutus, BigInt, BoundedBytes, Constr, DecodeError, DecodeErrorKind, KeyValuePairs,
+    MaybeIndefArray, PlutusData, create_constr,
+};
+
+/// A change made to one value inside some plutus data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MutationKind {
+    /// A constr was given a different variant, keeping its fields.
+    WrongVariant { from: u64, to: u64 },
+    /// The last field of a constr, or the last item of a list, was removed.
+    MissingField,
+    /// A field was added to the end of a constr, or an item to the end of a list.
+    ExtraField,
+    /// The value was replaced by a value of another type.
+    WrongType {
+        from: &'static str,
+        to: &'static str,
+    },
+    /// An integer was replaced by 2^128, which doesn't fit in any primitive integer.
+    OutOfRange,
+    /// A byte string was made one byte longer or shorter.
+    WrongLength { from: usize, to: usize },
+}
+
+impl MutationKind {
+    /// Whether `kind` is the error a type should report when it rejects this mutation.
+    pub fn fits(&self, kind: &DecodeErrorKind) -> bool {
+        use DecodeErrorKind as K;
+        match self {
+            Self::WrongVariant { .. } => matches!(
+                kind,
+                K::UnexpectedVariant { .. } | K::WrongVariantFieldCount { .. }
+            ),
+            Self::MissingField | Self::ExtraField => matches!(
+                kind,
+                K::WrongVariantFieldCount { .. }
+                    | K::WrongTupleFieldCount { .. }
+                    | K::WrongLength { .. }
+            ),
+            Self::WrongType { .. } => matches!(kind, K::UnexpectedType { .. }),
+            Self::OutOfRange => matches!(kind, K::OutOfRange { .. }),
+            Self::WrongLength { .. } => matches!(kind, K::WrongLength { .. }),
+        }
+    }
+}
+
+impl fmt::Display for MutationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongVariant { from, to } => write!(f, "variant {from} changed to {to}"),
+            Self::MissingField => f.write_str("last field removed"),
+            Self::ExtraField => f.write_str("extra field added"),
+            Self::WrongType { from, to } => write!(f, "{from} replaced with {to}"),
+            Self::OutOfRange => f.write_str("integer replaced with 2^128"),
+            Self::WrongLength { from, to } => write!(f, "length changed from {from} to {to}"),
+        }
+    }
+}
+
+/// Some plutus data with one value changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mutation {
+    pub kind: MutationKind,
+    /// Where the changed value is, in the same form as `DecodeError::positions`.
+    pub positions: Vec<usize>,
+    /// The whole data, including the change.
+    pub data: PlutusData,
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {:?}", self.kind, self.positions)
+    }
+}
+
+/// Every mutation of every value in `data`.
+pub fn mutations(data: &PlutusData) -> Vec<Mutation> {
+    let mut changes = vec![];
+    collect_changes(data, &mut vec![], &mut changes);
+    changes
+        .into_iter()
+        .map(|(kind, positions, value)| Mutation {
+            data: replace_at(data, &positions, value),
+            kind,
+            positions,
+        })
+        .collect()
+}
+
+/// What decoding a mutation did.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// Decoding failed at the changed value with a fitting error, or failed validation.
+    Rejected(DecodeError),
+    /// Decoding succeeded, and the value encodes back to the mutated data. The mutation
+    /// made another valid value, like a different integer.
+    Accepted,
+    /// Decoding failed, but somewhere else or with an error which doesn't fit.
+    Misreported(DecodeError),
+    /// Decoding succeeded, but the value encodes to something other than the mutated data,
+    /// so part of it was ignored.
+    Lossy,
+}
+
+/// A mutation and what decoding it did.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MutationResult {
+    pub mutation: Mutation,
+    pub outcome: Outcome,
+}
+
+impl MutationResult {
+    /// Whether the type handled the mutation correctly, by rejecting it where it was made
+    /// or by accepting it as another valid value.
+    pub fn is_ok(&self) -> bool {
+        matches!(self.outcome, Outcome::Rejected(_) | Outcome::Accepted)
+    }
+}
+
+impl fmt::Display for MutationResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.outcome {
+            Outcome::Rejected(error) => write!(f, "{}: rejected ({error})", self.mutation),
+            Outcome::Accepted => write!(f, "{}: accepted", self.mutation),
+            Outcome::Misreported(error) => write!(
+                f,
+                "{}: misreported ({error}, at {:?})",
+                self.mutation,
+                error.positions()
+            ),
+            Outcome::Lossy => write!(f, "{}: decoded, but lost data", self.mutation),
+        }
+    }
+}
+
+/// Decodes every mutation of the encoding of `value` as a `T`.
+pub fn check_mutations<T: AsPlutus>(value: T) -> Vec<MutationResult> {
+    mutations(&value.to_plutus())
+        .into_iter()
+        .map(|mutation| {
+            let outcome = match T::from_plutus(mutation.data.clone()) {
+                Ok(decoded) => {
+                    if decoded.to_plutus() == mutation.data {
+                        Outcome::Accepted
+                    } else {
+                        Outcome::Lossy
+                    }
+                }
+                Err(error) if rejects(&mutation, &error) => Outcome::Rejected(error),
+                Err(error) => Outcome::Misreported(error),
+            };
+            MutationResult { mutation, outcome }
+        })
+        .collect()
+}
+
+/// Checks that `T` handles every mutation of the encoding of `value` correctly, and
+/// panics with the ones it didn't otherwise.
+///
+/// A mutation is handled if decoding it fails at the changed value with a fitting
+/// `DecodeErrorKind` (or a validation error from the value or one containing it), or if
+/// it succeeds and the decoded value encodes back to exactly the mutated data.
+#[track_caller]
+pub fn assert_mutations<T: AsPlutus>(value: T) {
+    let failures: Vec<String> = check_mutations(value)
+        .into_iter()
+        .filter(|result| !result.is_ok())
+        .map(|result| format!("  {result}"))
+        .collect();
+    if !failures.is_empty() {
+        panic!("mishandled mutations:\n{}", failures.join("\n"));
+    }
+}
+
+fn rejects(mutation: &Mutation, error: &DecodeError) -> bool {
+    let positions = error.positions();
+    if let DecodeErrorKind::Validation(_) = error.kind() {
+        // constraints and validate functions check the changed value or one containing it
+        return mutation.positions.starts_with(&positions);
+    }
+    if positions == mutation.positions {
+        return mutation.kind.fits(error.kind());
+    }
+    // another variant can have the same fields with different types
+    matches!(mutation.kind, MutationKind::WrongVariant { .. })
+        && positions.starts_with(&mutation.positions)
+}
+
+type Change = (MutationKind, Vec<usize>, PlutusData);
+
+fn collect_changes(data: &PlutusData, positions: &mut Vec<usize>, changes: &mut Vec<Change>) {
+    let mut change = |kind, value| changes.push((kind, positions.clone(), value));
+    match data {
+        PlutusData::Constr(constr) => {
+            if let Some(from) = constr.constructor_value() {
+                let to = from.wrapping_add(1);
+                let kind = MutationKind::WrongVariant { from, to };
+                change(
+                    kind,
+                    create_constr(to, constr.fields.iter().cloned().collect()),
+                );
+            }
+            for (kind, fields) in resized(&constr.fields) {
+                let value = PlutusData::Constr(Constr {
+                    tag: constr.tag,
+                    any_constructor: constr.any_constructor,
+                    fields,
+                });
+                change(kind, value);
+            }
+            change(wrong_type("Constr", "BigInt"), int(0));
+        }
+        PlutusData::Array(items) => {
+            for (kind, items) in resized(items) {
+                change(kind, PlutusData::Array(items));
+            }
+            change(wrong_type("Array", "BigInt"), int(0));
+        }
+        PlutusData::Map(_) => change(wrong_type("Map", "BigInt"), int(0)),
+        PlutusData::BigInt(_) => {
+            change(MutationKind::OutOfRange, big_uint_2_128());
+            change(wrong_type("BigInt", "BoundedBytes"), bytes(vec![]));
+        }
+        PlutusData::BoundedBytes(value) => {
+            let mut longer = value.to_vec();
+            longer.push(0);
+            let from = value.len();
+            change(
+                MutationKind::WrongLength { from, to: from + 1 },
+                bytes(longer),
+            );
+            if let Some((_, shorter)) = value.split_last() {
+                change(
+                    MutationKind::WrongLength { from, to: from - 1 },
+                    bytes(shorter.to_vec()),
+                );
+            }
+            change(wrong_type("BoundedBytes", "BigInt"), int(0));
+        }
+    }
+    for (i, child) in children(data).into_iter().enumerate() {
+        positions.push(i);
+        collect_changes(child, positions, changes);
+        positions.pop();
+    }
+}
+
+// the values of a constr, list or map, in the order of their positions
+fn children(data: &PlutusData) -> Vec<&PlutusData> {
+    match data {
+        PlutusData::Constr(constr) => constr.fields.iter().collect(),
+        PlutusData::Array(items) => items.iter().collect(),
+        PlutusData::Map(kvps) => kvps.iter().flat_map(|(k, v)| [k, v]).collect(),
+        PlutusData::BigInt(_) | PlutusData::BoundedBytes(_) => vec![],
+    }
+}
+
+// the items with the last one removed, and with the last one repeated
+fn resized(
+    items: &MaybeIndefArray<PlutusData>,
+) -> Vec<(MutationKind, MaybeIndefArray<PlutusData>)> {
+    let mut changes = vec![];
+    if let Some((_, rest)) = items.split_last() {
+        changes.push((MutationKind::MissingField, same_form(items, rest.to_vec())));
+    }
+    let mut longer: Vec<_> = items.iter().cloned().collect();
+    longer.push(items.last().cloned().unwrap_or_else(|| int(0)));
+    changes.push((MutationKind::ExtraField, same_form(items, longer)));
+    changes
+}
+
+fn replace_at(data: &PlutusData, positions: &[usize], value: PlutusData) -> PlutusData {
+    let Some((&first, rest)) = positions.split_first() else {
+        return value;
+    };
+    let replace_item = |items: &MaybeIndefArray<PlutusData>| {
+        let mut new_items: Vec<_> = items.iter().cloned().collect();
+        new_items[first] = replace_at(&items[first], rest, value.clone());
+        same_form(items, new_items)
+    };
+    match data {
+        PlutusData::Constr(constr) => PlutusData::Constr(Constr {
+            tag: constr.tag,
+            any_constructor: constr.any_constructor,
+            fields: replace_item(&constr.fields),
+        }),
+        PlutusData::Array(items) => PlutusData::Array(replace_item(items)),
+        PlutusData::Map(kvps) => {
+            let mut entries: Vec<_> = kvps.iter().cloned().collect();
+            let (key, value_at) = &mut entries[first / 2];
+            let child = if first % 2 == 0 { key } else { value_at };
+            *child = replace_at(child, rest, value);
+            PlutusData::Map(match kvps {
+                KeyValuePairs::Def(_) => KeyValuePairs::Def(entries),
+                KeyValuePairs::Indef(_) => KeyValuePairs::Indef(entries),
+            })
+        }
+        PlutusData::BigInt(_) | PlutusData::BoundedBytes(_) => {
+            unreachable!("positions always lead to a constr, list or map")
+        }
+    }
+}
+
+fn same_form(
+    original: &MaybeIndefArray<PlutusData>,
+    items: Vec<PlutusData>,
+) -> MaybeIndefArray<PlutusData> {
+    match original {
+        MaybeIndefArray::Def(_) => MaybeIndefArray::Def(items),
+        MaybeIndefArray::Indef(_) => MaybeIndefArray::Indef(items),
+    }
+}
+
+fn wrong_type(from: &'static str, to: &'static str) -> MutationKind {
+    MutationKind::WrongType { from, to }
+}
+
+fn int(value: i64) -> PlutusData {
+    PlutusData::BigInt(BigInt::Int(value.into()))
+}
+
+fn bytes(value: Vec<u8>) -> PlutusData {
+    PlutusData::BoundedBytes(BoundedBytes::from(value))
+}
+
+fn big_uint_2_128() -> PlutusData {
+    let mut value = vec![1];
+    value.extend([0; 16]);
+    PlutusData::BigInt(BigInt::BigUInt(BoundedBytes::from(value)))
+}